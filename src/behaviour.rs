@@ -0,0 +1,152 @@
+//! Entity systems attached to the entities as components.
+//! Every entity runs it's own behaviours, see [`run_behaviours`]
+
+use crate::{
+    dyn_entity_system::BoxedEntitySystem,
+    into_entity_system::IntoEntitySystem,
+};
+use bevy_ecs::{
+    change_detection::DetectChangesMut,
+    component::Component,
+    entity::Entity,
+    query::{Or, QueryState, With},
+    world::World,
+};
+use std::mem;
+
+/// Component that holds single entity system, that is run for the entity it's attached to
+/// by [`run_behaviours`]. Use [`Behaviours`] to attach multiple entity systems.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_entity_system::prelude::*;
+/// #[derive(Component)]
+/// struct Position(i32);
+///
+/// fn patrol(mut data: Data<&mut Position>) {
+///     data.0 = (data.0 + 1) % 10;
+/// }
+///
+/// let mut world = World::new();
+/// let entity = world.spawn((Position(0), Behaviour::new(patrol))).id();
+///
+/// let mut schedule = Schedule::default();
+/// schedule.add_systems(run_behaviours);
+///
+/// schedule.run(&mut world);
+/// schedule.run(&mut world);
+///
+/// assert_eq!(world.get::<Position>(entity).unwrap().0, 2);
+/// ```
+#[derive(Component)]
+pub struct Behaviour(Option<BoxedEntitySystem>);
+
+impl Behaviour {
+    /// Creates behaviour from the entity system
+    #[inline]
+    pub fn new<Marker>(system: impl IntoEntitySystem<(), (), Marker>) -> Self {
+        Behaviour(Some(system.boxed()))
+    }
+
+    /// Resets the state that the entity system stores for the `entity`.
+    /// See [`EntitySystem::reset`](crate::EntitySystem::reset)
+    #[inline]
+    pub fn reset(&mut self, entity: Entity) {
+        if let Some(system) = &mut self.0 {
+            system.reset(entity);
+        }
+    }
+}
+
+/// Component that holds entity systems, that are run for the entity it's attached to
+/// by [`run_behaviours`] in the order they were added.
+#[derive(Component, Default)]
+pub struct Behaviours(Vec<BoxedEntitySystem>);
+
+impl Behaviours {
+    /// Creates empty behaviours
+    #[inline]
+    pub fn new() -> Self {
+        Behaviours(Vec::new())
+    }
+
+    /// Adds entity system to the behaviours
+    #[inline]
+    pub fn with<Marker>(mut self, system: impl IntoEntitySystem<(), (), Marker>) -> Self {
+        self.push(system);
+        self
+    }
+
+    /// Adds entity system to the behaviours
+    #[inline]
+    pub fn push<Marker>(&mut self, system: impl IntoEntitySystem<(), (), Marker>) {
+        self.0.push(system.boxed());
+    }
+
+    /// Resets the state that every entity system stores for the `entity`.
+    /// See [`EntitySystem::reset`](crate::EntitySystem::reset)
+    #[inline]
+    pub fn reset(&mut self, entity: Entity) {
+        for system in &mut self.0 {
+            system.reset(entity);
+        }
+    }
+
+    /// Number of entity systems
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if there is no entity systems
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+type BehaviourQueryState = QueryState<Entity, Or<(With<Behaviour>, With<Behaviours>)>>;
+
+/// Exclusive system that runs [`Behaviour`] and [`Behaviours`] of every entity for that entity.
+/// State of each behaviour is initialized on it's first run and deferred mutations are applied
+/// right after it.
+///
+/// If entity doesn't match `Query<EntitySystem::Data, EntitySystem::Filter>` of the behaviour,
+/// behaviour is skipped. Behaviours pushed to the entity while it's behaviours are run
+/// are kept and will be run next time.
+pub fn run_behaviours(world: &mut World, query: &mut BehaviourQueryState) {
+    let entities: Vec<Entity> = query.iter(world).collect();
+
+    for entity in entities {
+        let behaviour = world
+            .get_mut::<Behaviour>(entity)
+            .and_then(|mut behaviour| behaviour.bypass_change_detection().0.take());
+
+        if let Some(mut system) = behaviour {
+            let _ = system.run((), entity, world);
+
+            if let Some(mut behaviour) = world.get_mut::<Behaviour>(entity) {
+                let behaviour = behaviour.bypass_change_detection();
+                if behaviour.0.is_none() {
+                    behaviour.0 = Some(system);
+                }
+            }
+        }
+
+        let behaviours = world
+            .get_mut::<Behaviours>(entity)
+            .map(|mut behaviours| mem::take(&mut behaviours.bypass_change_detection().0));
+
+        if let Some(mut systems) = behaviours {
+            for system in &mut systems {
+                let _ = system.run((), entity, world);
+            }
+
+            if let Some(mut behaviours) = world.get_mut::<Behaviours>(entity) {
+                let behaviours = behaviours.bypass_change_detection();
+                systems.append(&mut behaviours.0);
+                behaviours.0 = systems;
+            }
+        }
+    }
+}
@@ -0,0 +1,611 @@
+//! Behaviour trees built out of [`EntitySystem`]s that return [`Status`].
+//!
+//! Composite nodes run their children in order and store progress of the running child for every entity,
+//! so it's continued on the next run for that entity.
+//! Child that can't be run for the entity is treated as it returned [`Status::Failure`],
+//! but composite node can only be run for the entities that match at least one of it's children.
+//!
+//! When composite node finishes, progress of all it's children for the entity is reset with
+//! [`EntitySystem::reset`], so aborted children start over next time.
+//! Progress of the despawned entities is dropped.
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_entity_system::prelude::*;
+//! # use bevy_entity_system::behaviour_tree::{invert, selector, sequence};
+//! #[derive(Component)]
+//! struct Health(i32);
+//!
+//! #[derive(Component)]
+//! struct Position(i32);
+//!
+//! fn is_hurt(data: Data<&Health>) -> Status {
+//!     Status::from(data.0 < 50)
+//! }
+//!
+//! fn flee(mut data: Data<&mut Position>) -> Status {
+//!     data.0 -= 1;
+//!     if data.0 <= 0 {
+//!         Status::Success
+//!     } else {
+//!         Status::Running
+//!     }
+//! }
+//!
+//! fn patrol(mut data: Data<&mut Position>) -> Status {
+//!     data.0 += 1;
+//!     Status::Running
+//! }
+//!
+//! let tree = selector((sequence((is_hurt, flee)), sequence((invert(is_hurt), patrol))));
+//!
+//! let mut world = World::new();
+//! let entity = world.spawn((Health(20), Position(2))).id();
+//! let tree = world.register_entity_system(tree);
+//!
+//! assert_eq!(world.run_registered_entity_system(tree, entity, ()), Ok(Status::Running));
+//! assert_eq!(world.run_registered_entity_system(tree, entity, ()), Ok(Status::Success));
+//! ```
+
+use crate::{data_match::DataMatch, into_entity_system::IntoEntitySystem, EntitySystem};
+use bevy_ecs::{
+    entity::{Entities, Entity, EntityHashMap},
+    query::{Or, QueryFilter, QueryItem},
+    system::{lifetimeless::SQuery, ParamSet, SystemParam, SystemParamItem},
+};
+use bevy_entity_system_macros::IntoSystem;
+
+/// Result of the run of the behaviour tree node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// Node has finished successfully
+    Success,
+    /// Node has failed
+    Failure,
+    /// Node hasn't finished yet and should be run again
+    Running,
+}
+
+impl Status {
+    /// Swaps [`Success`](Status::Success) and [`Failure`](Status::Failure).
+    /// [`Running`](Status::Running) stays the same
+    #[inline]
+    pub fn invert(self) -> Self {
+        match self {
+            Status::Success => Status::Failure,
+            Status::Failure => Status::Success,
+            Status::Running => Status::Running,
+        }
+    }
+}
+
+impl From<bool> for Status {
+    /// `true` is [`Success`](Status::Success), `false` is [`Failure`](Status::Failure)
+    #[inline]
+    fn from(value: bool) -> Self {
+        if value {
+            Status::Success
+        } else {
+            Status::Failure
+        }
+    }
+}
+
+/// Tuple of [`EntitySystem`]s that return [`Status`] and are children of the composite node.
+/// Implemented for tuples of up to 8 elements
+pub trait EntitySystemNodes: Send + Sync + 'static {
+    /// Input of every node
+    type In: Clone;
+    /// [`SystemParam`]'s of all the nodes
+    type Param: SystemParam;
+    /// Filter that matches entities for which at least one node can be run
+    type Filter: QueryFilter;
+
+    /// Number of nodes
+    const LEN: usize;
+
+    /// Runs node with the `index` for the `entity`.
+    /// Returns [`Status::Failure`] if node can't be run for the entity.
+    fn run_node(
+        &mut self,
+        index: usize,
+        input: Self::In,
+        entity: Entity,
+        param_value: &mut SystemParamItem<Self::Param>,
+    ) -> Status;
+
+    /// Resets every node for the `entity`. See [`EntitySystem::reset`]
+    fn reset(&mut self, entity: Entity);
+}
+
+/// Conversion of the tuple into [`EntitySystemNodes`].
+/// Implemented for tuples of [`IntoEntitySystem`]s that return [`Status`]
+pub trait IntoEntitySystemNodes<In, Marker> {
+    /// Converted tuple
+    type Nodes: EntitySystemNodes<In = In>;
+
+    /// Converts tuple into [`EntitySystemNodes`]
+    fn into_nodes(self) -> Self::Nodes;
+}
+
+macro_rules! impl_entity_system_nodes {
+    ($len: expr, $(($index: tt, $p: ident, $system: ident, $marker: ident)),*) => {
+        impl<In: Clone, $($system: EntitySystem<In = In, Out = Status>),*> EntitySystemNodes
+            for ($($system,)*)
+        {
+            type In = In;
+            type Param = ParamSet<
+                'static,
+                'static,
+                ($((SQuery<$system::Data, $system::Filter>, $system::Param),)*),
+            >;
+            type Filter = Or<($((DataMatch<$system::Data>, $system::Filter),)*)>;
+
+            const LEN: usize = $len;
+
+            fn run_node(
+                &mut self,
+                index: usize,
+                input: Self::In,
+                entity: Entity,
+                param_value: &mut SystemParamItem<Self::Param>,
+            ) -> Status {
+                match index {
+                    $($index => {
+                        let (mut query, param) = param_value.$p();
+                        let status = match query.get_mut(entity) {
+                            Ok(data) => self.$index.run(input, entity, data, param),
+                            Err(_) => Status::Failure,
+                        };
+                        status
+                    })*
+                    _ => panic!("node index {index} is out of bounds"),
+                }
+            }
+
+            #[inline]
+            fn reset(&mut self, entity: Entity) {
+                $(self.$index.reset(entity);)*
+            }
+        }
+
+        impl<In: Clone, $($system: IntoEntitySystem<In, Status, $marker>, $marker),*>
+            IntoEntitySystemNodes<In, ($($marker,)*)> for ($($system,)*)
+        {
+            type Nodes = ($($system::EntitySystem,)*);
+
+            #[inline]
+            fn into_nodes(self) -> Self::Nodes {
+                ($(self.$index.into_entity_system(),)*)
+            }
+        }
+    };
+}
+
+impl_entity_system_nodes!(1, (0, p0, A, MA));
+impl_entity_system_nodes!(2, (0, p0, A, MA), (1, p1, B, MB));
+impl_entity_system_nodes!(3, (0, p0, A, MA), (1, p1, B, MB), (2, p2, C, MC));
+impl_entity_system_nodes!(
+    4,
+    (0, p0, A, MA),
+    (1, p1, B, MB),
+    (2, p2, C, MC),
+    (3, p3, D, MD)
+);
+impl_entity_system_nodes!(
+    5,
+    (0, p0, A, MA),
+    (1, p1, B, MB),
+    (2, p2, C, MC),
+    (3, p3, D, MD),
+    (4, p4, E, ME)
+);
+impl_entity_system_nodes!(
+    6,
+    (0, p0, A, MA),
+    (1, p1, B, MB),
+    (2, p2, C, MC),
+    (3, p3, D, MD),
+    (4, p4, E, ME),
+    (5, p5, F, MF)
+);
+impl_entity_system_nodes!(
+    7,
+    (0, p0, A, MA),
+    (1, p1, B, MB),
+    (2, p2, C, MC),
+    (3, p3, D, MD),
+    (4, p4, E, ME),
+    (5, p5, F, MF),
+    (6, p6, G, MG)
+);
+impl_entity_system_nodes!(
+    8,
+    (0, p0, A, MA),
+    (1, p1, B, MB),
+    (2, p2, C, MC),
+    (3, p3, D, MD),
+    (4, p4, E, ME),
+    (5, p5, F, MF),
+    (6, p6, G, MG),
+    (7, p7, H, MH)
+);
+
+/// Progress of the node for every entity.
+/// Entries of the despawned entities are pruned every time the number of entries doubles
+struct Progress<V> {
+    entries: EntityHashMap<V>,
+    pruned_len: usize,
+}
+
+impl<V> Default for Progress<V> {
+    fn default() -> Self {
+        Progress {
+            entries: EntityHashMap::default(),
+            pruned_len: 0,
+        }
+    }
+}
+
+impl<V> Progress<V> {
+    #[inline]
+    fn take(&mut self, entity: Entity) -> Option<V> {
+        self.entries.remove(&entity)
+    }
+
+    fn store(&mut self, entity: Entity, value: V, entities: &Entities) {
+        self.entries.insert(entity, value);
+
+        if self.entries.len() > self.pruned_len * 2 {
+            self.entries.retain(|entity, _| entities.contains(*entity));
+            self.pruned_len = self.entries.len();
+        }
+    }
+}
+
+/// Composite node that runs it's children in order until one of them fails.
+///
+/// Returns [`Status::Failure`] as soon as any child fails, [`Status::Success`] if all the children succeeded.
+/// If child returns [`Status::Running`], sequence returns it too and continues from that child on the next run.
+#[derive(IntoSystem)]
+pub struct Sequence<T: EntitySystemNodes> {
+    nodes: T,
+    running: Progress<usize>,
+}
+
+impl<T: EntitySystemNodes> EntitySystem for Sequence<T> {
+    type Data = Entity;
+    type Filter = T::Filter;
+    type Param = (T::Param, &'static Entities);
+
+    type In = T::In;
+    type Out = Status;
+
+    fn run(
+        &mut self,
+        input: Self::In,
+        entity: Entity,
+        _data_value: QueryItem<Self::Data>,
+        param_value: SystemParamItem<Self::Param>,
+    ) -> Self::Out {
+        let (mut param_value, entities) = param_value;
+        let start = self.running.take(entity).unwrap_or(0);
+
+        for index in start..T::LEN {
+            match self
+                .nodes
+                .run_node(index, input.clone(), entity, &mut param_value)
+            {
+                Status::Success => {}
+                Status::Failure => {
+                    self.nodes.reset(entity);
+                    return Status::Failure;
+                }
+                Status::Running => {
+                    self.running.store(entity, index, entities);
+                    return Status::Running;
+                }
+            }
+        }
+
+        self.nodes.reset(entity);
+        Status::Success
+    }
+
+    fn reset(&mut self, entity: Entity) {
+        self.running.take(entity);
+        self.nodes.reset(entity);
+    }
+}
+
+/// See [`Sequence`]
+#[inline]
+pub fn sequence<In, Marker, T: IntoEntitySystemNodes<In, Marker>>(nodes: T) -> Sequence<T::Nodes> {
+    Sequence {
+        nodes: nodes.into_nodes(),
+        running: Progress::default(),
+    }
+}
+
+/// Composite node that runs it's children in order until one of them succeeds.
+///
+/// Returns [`Status::Success`] as soon as any child succeeds, [`Status::Failure`] if all the children failed.
+/// If child returns [`Status::Running`], selector returns it too and continues from that child on the next run.
+#[derive(IntoSystem)]
+pub struct Selector<T: EntitySystemNodes> {
+    nodes: T,
+    running: Progress<usize>,
+}
+
+impl<T: EntitySystemNodes> EntitySystem for Selector<T> {
+    type Data = Entity;
+    type Filter = T::Filter;
+    type Param = (T::Param, &'static Entities);
+
+    type In = T::In;
+    type Out = Status;
+
+    fn run(
+        &mut self,
+        input: Self::In,
+        entity: Entity,
+        _data_value: QueryItem<Self::Data>,
+        param_value: SystemParamItem<Self::Param>,
+    ) -> Self::Out {
+        let (mut param_value, entities) = param_value;
+        let start = self.running.take(entity).unwrap_or(0);
+
+        for index in start..T::LEN {
+            match self
+                .nodes
+                .run_node(index, input.clone(), entity, &mut param_value)
+            {
+                Status::Success => {
+                    self.nodes.reset(entity);
+                    return Status::Success;
+                }
+                Status::Failure => {}
+                Status::Running => {
+                    self.running.store(entity, index, entities);
+                    return Status::Running;
+                }
+            }
+        }
+
+        self.nodes.reset(entity);
+        Status::Failure
+    }
+
+    fn reset(&mut self, entity: Entity) {
+        self.running.take(entity);
+        self.nodes.reset(entity);
+    }
+}
+
+/// See [`Selector`]
+#[inline]
+pub fn selector<In, Marker, T: IntoEntitySystemNodes<In, Marker>>(nodes: T) -> Selector<T::Nodes> {
+    Selector {
+        nodes: nodes.into_nodes(),
+        running: Progress::default(),
+    }
+}
+
+/// Composite node that runs all of it's children on every run.
+///
+/// Returns [`Status::Failure`] as soon as any child fails, [`Status::Success`] when all the children succeeded.
+/// Children that succeeded aren't run again until parallel finishes.
+#[derive(IntoSystem)]
+pub struct Parallel<T: EntitySystemNodes> {
+    nodes: T,
+    succeeded: Progress<Vec<bool>>,
+}
+
+impl<T: EntitySystemNodes> EntitySystem for Parallel<T> {
+    type Data = Entity;
+    type Filter = T::Filter;
+    type Param = (T::Param, &'static Entities);
+
+    type In = T::In;
+    type Out = Status;
+
+    fn run(
+        &mut self,
+        input: Self::In,
+        entity: Entity,
+        _data_value: QueryItem<Self::Data>,
+        param_value: SystemParamItem<Self::Param>,
+    ) -> Self::Out {
+        let (mut param_value, entities) = param_value;
+        let mut succeeded = self
+            .succeeded
+            .take(entity)
+            .unwrap_or_else(|| vec![false; T::LEN]);
+        let mut running = false;
+
+        for (index, succeeded) in succeeded.iter_mut().enumerate() {
+            if *succeeded {
+                continue;
+            }
+
+            match self
+                .nodes
+                .run_node(index, input.clone(), entity, &mut param_value)
+            {
+                Status::Success => *succeeded = true,
+                Status::Failure => {
+                    self.nodes.reset(entity);
+                    return Status::Failure;
+                }
+                Status::Running => running = true,
+            }
+        }
+
+        if running {
+            self.succeeded.store(entity, succeeded, entities);
+            Status::Running
+        } else {
+            self.nodes.reset(entity);
+            Status::Success
+        }
+    }
+
+    fn reset(&mut self, entity: Entity) {
+        self.succeeded.take(entity);
+        self.nodes.reset(entity);
+    }
+}
+
+/// See [`Parallel`]
+#[inline]
+pub fn parallel<In, Marker, T: IntoEntitySystemNodes<In, Marker>>(nodes: T) -> Parallel<T::Nodes> {
+    Parallel {
+        nodes: nodes.into_nodes(),
+        succeeded: Progress::default(),
+    }
+}
+
+/// Decorator node that swaps [`Status::Success`] and [`Status::Failure`] of the child.
+#[derive(IntoSystem, Clone)]
+pub struct Invert<T: EntitySystem<Out = Status>>(T);
+
+impl<T: EntitySystem<Out = Status>> EntitySystem for Invert<T> {
+    type Data = T::Data;
+    type Filter = T::Filter;
+    type Param = T::Param;
+
+    type In = T::In;
+    type Out = Status;
+
+    #[inline]
+    fn run(
+        &mut self,
+        input: Self::In,
+        entity: Entity,
+        data_value: QueryItem<Self::Data>,
+        param_value: SystemParamItem<Self::Param>,
+    ) -> Self::Out {
+        self.0.run(input, entity, data_value, param_value).invert()
+    }
+
+    #[inline]
+    fn reset(&mut self, entity: Entity) {
+        self.0.reset(entity);
+    }
+}
+
+/// See [`Invert`]
+#[inline]
+pub fn invert<In, Marker, T: IntoEntitySystem<In, Status, Marker>>(
+    system: T,
+) -> Invert<T::EntitySystem> {
+    Invert(system.into_entity_system())
+}
+
+/// Decorator node that succeeds after the child succeeded `count` times.
+///
+/// Returns [`Status::Running`] until then, fails as soon as the child fails.
+#[derive(IntoSystem)]
+pub struct Repeat<T: EntitySystem<Out = Status>> {
+    system: T,
+    count: usize,
+    completed: Progress<usize>,
+}
+
+impl<T: EntitySystem<Out = Status>> EntitySystem for Repeat<T> {
+    type Data = T::Data;
+    type Filter = T::Filter;
+    type Param = (T::Param, &'static Entities);
+
+    type In = T::In;
+    type Out = Status;
+
+    fn run(
+        &mut self,
+        input: Self::In,
+        entity: Entity,
+        data_value: QueryItem<Self::Data>,
+        param_value: SystemParamItem<Self::Param>,
+    ) -> Self::Out {
+        if self.count == 0 {
+            return Status::Success;
+        }
+
+        let (param_value, entities) = param_value;
+        match self.system.run(input, entity, data_value, param_value) {
+            Status::Success => {
+                let completed = self.completed.take(entity).unwrap_or(0) + 1;
+
+                if completed >= self.count {
+                    Status::Success
+                } else {
+                    self.completed.store(entity, completed, entities);
+                    Status::Running
+                }
+            }
+            Status::Failure => {
+                self.completed.take(entity);
+                Status::Failure
+            }
+            Status::Running => Status::Running,
+        }
+    }
+
+    fn reset(&mut self, entity: Entity) {
+        self.completed.take(entity);
+        self.system.reset(entity);
+    }
+}
+
+/// See [`Repeat`]
+#[inline]
+pub fn repeat<In, Marker, T: IntoEntitySystem<In, Status, Marker>>(
+    count: usize,
+    system: T,
+) -> Repeat<T::EntitySystem> {
+    Repeat {
+        system: system.into_entity_system(),
+        count,
+        completed: Progress::default(),
+    }
+}
+
+/// Decorator node that returns [`Status::Running`] until the child succeeds.
+#[derive(IntoSystem, Clone)]
+pub struct UntilSuccess<T: EntitySystem<Out = Status>>(T);
+
+impl<T: EntitySystem<Out = Status>> EntitySystem for UntilSuccess<T> {
+    type Data = T::Data;
+    type Filter = T::Filter;
+    type Param = T::Param;
+
+    type In = T::In;
+    type Out = Status;
+
+    #[inline]
+    fn run(
+        &mut self,
+        input: Self::In,
+        entity: Entity,
+        data_value: QueryItem<Self::Data>,
+        param_value: SystemParamItem<Self::Param>,
+    ) -> Self::Out {
+        match self.0.run(input, entity, data_value, param_value) {
+            Status::Success => Status::Success,
+            Status::Failure | Status::Running => Status::Running,
+        }
+    }
+
+    #[inline]
+    fn reset(&mut self, entity: Entity) {
+        self.0.reset(entity);
+    }
+}
+
+/// See [`UntilSuccess`]
+#[inline]
+pub fn until_success<In, Marker, T: IntoEntitySystem<In, Status, Marker>>(
+    system: T,
+) -> UntilSuccess<T::EntitySystem> {
+    UntilSuccess(system.into_entity_system())
+}
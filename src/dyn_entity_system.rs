@@ -0,0 +1,93 @@
+//! Type-erased [`EntitySystem`]s, that can be stored together regardless of their concrete type.
+//! Similar to [`BoxedSystem`](bevy_ecs::system::BoxedSystem)
+
+use crate::{EntityMismatch, EntitySystem};
+use bevy_ecs::{
+    entity::Entity,
+    system::{lifetimeless::SQuery, SystemState},
+    world::World,
+};
+
+/// Object safe version of [`EntitySystem`] that stores state of it's query and params.
+/// Can be used to store entity systems of different types together,
+/// see [`BoxedEntitySystem`].
+///
+/// Any [`IntoEntitySystem`](crate::into_entity_system::IntoEntitySystem) can be converted into
+/// it with [`boxed`](crate::into_entity_system::IntoEntitySystem::boxed).
+pub trait DynEntitySystem<In, Out>: Send + Sync + 'static {
+    /// Initializes the state of the system using the `world`.
+    /// Does nothing if system is already initialized.
+    fn initialize(&mut self, world: &mut World);
+
+    /// Initializes the system if needed, runs it once for the `entity` and applies deferred mutations.
+    /// Returns [`EntityMismatch`] if entity doesn't match
+    /// `Query<EntitySystem::Data, EntitySystem::Filter>`.
+    ///
+    /// # Panics
+    /// If system was initialized with another `world`.
+    fn run(&mut self, input: In, entity: Entity, world: &mut World) -> Result<Out, EntityMismatch>;
+
+    /// Resets the state that the system stores for the `entity`.
+    /// See [`EntitySystem::reset`]
+    fn reset(&mut self, entity: Entity);
+}
+
+/// Type-erased [`EntitySystem`]. See [`DynEntitySystem`]
+pub type BoxedEntitySystem<In = (), Out = ()> = Box<dyn DynEntitySystem<In, Out>>;
+
+type EntitySystemState<T> = SystemState<(
+    SQuery<<T as EntitySystem>::Data, <T as EntitySystem>::Filter>,
+    <T as EntitySystem>::Param,
+)>;
+
+/// [`EntitySystem`] together with the state of it's query and params.
+/// State is initialized on the first run
+pub(crate) struct CachedEntitySystem<T: EntitySystem> {
+    system: T,
+    state: Option<EntitySystemState<T>>,
+}
+
+impl<T: EntitySystem> CachedEntitySystem<T> {
+    #[inline]
+    pub(crate) fn new(system: T) -> Self {
+        CachedEntitySystem {
+            system,
+            state: None,
+        }
+    }
+}
+
+impl<T: EntitySystem> DynEntitySystem<T::In, T::Out> for CachedEntitySystem<T> {
+    #[inline]
+    fn initialize(&mut self, world: &mut World) {
+        if self.state.is_none() {
+            self.state = Some(SystemState::new(world));
+        }
+    }
+
+    fn run(
+        &mut self,
+        input: T::In,
+        entity: Entity,
+        world: &mut World,
+    ) -> Result<T::Out, EntityMismatch> {
+        self.initialize(world);
+        let state = self.state.as_mut().unwrap();
+
+        let result = {
+            let (mut query, param) = state.get_mut(world);
+            let result = match query.get_mut(entity) {
+                Ok(data) => Ok(self.system.run(input, entity, data, param)),
+                Err(_) => Err(EntityMismatch(entity)),
+            };
+            result
+        };
+        state.apply(world);
+        result
+    }
+
+    #[inline]
+    fn reset(&mut self, entity: Entity) {
+        self.system.reset(entity);
+    }
+}
@@ -0,0 +1,281 @@
+//! Parameters of the functions that can be treated as [`EntitySystem`](crate::EntitySystem)s,
+//! that depend on the entity system is being run on
+
+use bevy_ecs::{
+    bundle::Bundle,
+    component::Component,
+    entity::Entity,
+    system::{
+        Commands, EntityCommand, EntityCommands, Local, Query, SystemParam, SystemParamItem,
+    },
+    world::{FromWorld, World},
+};
+use bevy_utils::HashMap;
+use std::{
+    ops::{Deref, DerefMut},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Parameter of the function that can be treated as [`EntitySystem`](crate::EntitySystem).
+/// Implemented for every [`SystemParam`] and for the params that need to know
+/// the entity system is being run on, such as [`SelfCommands`].
+pub trait EntitySystemParam {
+    /// [`SystemParam`] that is fetched from the world
+    type Param: SystemParam;
+    /// Type that is passed to the function
+    type Item<'w, 's>;
+
+    /// Creates the item from the fetched [`Param`](EntitySystemParam::Param)
+    /// for the `entity` system is being run on
+    fn get_param<'w, 's>(
+        entity: Entity,
+        param_value: SystemParamItem<'w, 's, Self::Param>,
+    ) -> Self::Item<'w, 's>;
+}
+
+/// Shorthand way of accessing the associated type [`EntitySystemParam::Item`]
+/// for a given [`EntitySystemParam`].
+pub type EntitySystemParamItem<'w, 's, P> = <P as EntitySystemParam>::Item<'w, 's>;
+
+impl<P: SystemParam> EntitySystemParam for P {
+    type Param = P;
+    type Item<'w, 's> = SystemParamItem<'w, 's, P>;
+
+    #[inline]
+    fn get_param<'w, 's>(
+        _entity: Entity,
+        param_value: SystemParamItem<'w, 's, Self::Param>,
+    ) -> Self::Item<'w, 's> {
+        param_value
+    }
+}
+
+/// [`Commands`] bound to the entity system is being run on.
+/// Allows entity system to structurally mutate it's own entity.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_entity_system::prelude::*;
+/// #[derive(Component)]
+/// struct Health(i32);
+///
+/// #[derive(Component)]
+/// struct Dead;
+///
+/// fn die(data: Data<&Health, Without<Dead>>, mut this: SelfCommands) {
+///     if data.0 <= 0 {
+///         this.insert(Dead);
+///     }
+/// }
+/// # bevy_ecs::system::assert_is_system(die.into_system());
+/// ```
+pub struct SelfCommands<'w, 's> {
+    commands: Commands<'w, 's>,
+    entity: Entity,
+}
+
+impl<'w, 's> EntitySystemParam for SelfCommands<'w, 's> {
+    type Param = Commands<'static, 'static>;
+    type Item<'w2, 's2> = SelfCommands<'w2, 's2>;
+
+    #[inline]
+    fn get_param<'w2, 's2>(
+        entity: Entity,
+        param_value: SystemParamItem<'w2, 's2, Self::Param>,
+    ) -> Self::Item<'w2, 's2> {
+        SelfCommands {
+            commands: param_value,
+            entity,
+        }
+    }
+}
+
+impl<'w, 's> SelfCommands<'w, 's> {
+    /// Entity system is being run on
+    #[inline]
+    pub fn id(&self) -> Entity {
+        self.entity
+    }
+
+    /// Returns [`EntityCommands`] for the entity system is being run on
+    #[inline]
+    pub fn entity_commands(&mut self) -> EntityCommands<'_> {
+        self.commands.entity(self.entity)
+    }
+
+    /// Returns underlying [`Commands`]
+    #[inline]
+    pub fn commands(&mut self) -> &mut Commands<'w, 's> {
+        &mut self.commands
+    }
+
+    /// Adds a [`Bundle`] of components to the entity. See [`EntityCommands::insert`]
+    #[inline]
+    pub fn insert(&mut self, bundle: impl Bundle) -> &mut Self {
+        self.entity_commands().insert(bundle);
+        self
+    }
+
+    /// Tries to add a [`Bundle`] of components to the entity. See [`EntityCommands::try_insert`]
+    #[inline]
+    pub fn try_insert(&mut self, bundle: impl Bundle) -> &mut Self {
+        self.entity_commands().try_insert(bundle);
+        self
+    }
+
+    /// Removes a [`Bundle`] of components from the entity. See [`EntityCommands::remove`]
+    #[inline]
+    pub fn remove<T: Bundle>(&mut self) -> &mut Self {
+        self.entity_commands().remove::<T>();
+        self
+    }
+
+    /// Despawns the entity. See [`EntityCommands::despawn`]
+    #[inline]
+    pub fn despawn(&mut self) {
+        self.entity_commands().despawn();
+    }
+
+    /// Pushes an [`EntityCommand`] to the queue. See [`EntityCommands::add`]
+    #[inline]
+    pub fn add<M: 'static>(&mut self, command: impl EntityCommand<M>) -> &mut Self {
+        self.entity_commands().add(command);
+        self
+    }
+}
+
+/// Hidden component that stores the values of the [`EntityLocal`]s with the type `T`,
+/// keyed by the [`EntityLocalId`] of every param
+#[doc(hidden)]
+#[derive(Component)]
+pub struct EntityLocalValues<T: Send + Sync + 'static>(HashMap<u64, T>);
+
+/// Identifier of the [`EntityLocal`], that is unique for every state of the param.
+/// Allocated when the state is initialized
+#[doc(hidden)]
+pub struct EntityLocalId(u64);
+
+impl FromWorld for EntityLocalId {
+    fn from_world(_world: &mut World) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        EntityLocalId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// Value that is local to the entity system is being run on.
+/// Works like [`Local`], but every entity has it's own value for every system,
+/// that is preserved between the runs of that system.
+///
+/// Value is stored in the hidden component on the entity, keyed by the state of the param,
+/// so systems using the same `T` don't share values. On the first run
+/// for the entity, it's initialized with [`Default`] and is inserted to the entity
+/// using [`Commands`] after the run, so it's only available after commands are applied.
+/// Systems created with [`into_stateful_system`](crate::into_entity_system::EntitySystemIntoSystem::into_stateful_system)
+/// apply commands right after every run, so value is inserted immediately.
+/// They also have separate param state for every entity, so the value is reset
+/// when that state is dropped.
+///
+/// Two `EntityLocal`s with the same `T` in one system will conflict.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_entity_system::prelude::*;
+/// #[derive(Component)]
+/// struct Count(i32);
+///
+/// fn count_runs(mut data: Data<&mut Count>, mut runs: EntityLocal<i32>) {
+///     *runs += 1;
+///     data.0 = *runs;
+/// }
+/// # bevy_ecs::system::assert_is_system(count_runs.into_system());
+/// ```
+pub struct EntityLocal<'w, 's, T: Default + Send + Sync + 'static> {
+    query: Query<'w, 's, &'static mut EntityLocalValues<T>>,
+    commands: Commands<'w, 's>,
+    id: u64,
+    entity: Entity,
+    pending: Option<T>,
+}
+
+impl<'w, 's, T: Default + Send + Sync + 'static> EntitySystemParam for EntityLocal<'w, 's, T> {
+    type Param = (
+        Query<'static, 'static, &'static mut EntityLocalValues<T>>,
+        Commands<'static, 'static>,
+        Local<'static, EntityLocalId>,
+    );
+    type Item<'w2, 's2> = EntityLocal<'w2, 's2, T>;
+
+    #[inline]
+    fn get_param<'w2, 's2>(
+        entity: Entity,
+        param_value: SystemParamItem<'w2, 's2, Self::Param>,
+    ) -> Self::Item<'w2, 's2> {
+        let (query, commands, id) = param_value;
+        let id = id.0;
+        let pending = query
+            .get(entity)
+            .map_or(true, |values| !values.0.contains_key(&id))
+            .then(T::default);
+
+        EntityLocal {
+            query,
+            commands,
+            id,
+            entity,
+            pending,
+        }
+    }
+}
+
+impl<'w, 's, T: Default + Send + Sync + 'static> Deref for EntityLocal<'w, 's, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        match &self.pending {
+            Some(value) => value,
+            None => &self.query.get(self.entity).unwrap().0[&self.id],
+        }
+    }
+}
+
+impl<'w, 's, T: Default + Send + Sync + 'static> DerefMut for EntityLocal<'w, 's, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match &mut self.pending {
+            Some(value) => value,
+            None => self
+                .query
+                .get_mut(self.entity)
+                .unwrap()
+                .into_inner()
+                .0
+                .get_mut(&self.id)
+                .unwrap(),
+        }
+    }
+}
+
+impl<'w, 's, T: Default + Send + Sync + 'static> Drop for EntityLocal<'w, 's, T> {
+    fn drop(&mut self) {
+        if let Some(value) = self.pending.take() {
+            let id = self.id;
+            self.commands
+                .entity(self.entity)
+                .add(move |entity: Entity, world: &mut World| {
+                    let Some(mut entity) = world.get_entity_mut(entity) else {
+                        return;
+                    };
+
+                    match entity.get_mut::<EntityLocalValues<T>>() {
+                        Some(mut values) => {
+                            values.0.insert(id, value);
+                        }
+                        None => {
+                            entity.insert(EntityLocalValues(HashMap::from_iter([(id, value)])));
+                        }
+                    }
+                });
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Running [`EntitySystem`]s on the lifecycle events of the components.
+//! See [`OnAdd`], [`OnInsert`] and [`OnRemove`]
+
+use crate::{
+    into_entity_system::{EntitySystemQuery, IntoEntitySystem},
+    EntitySystem,
+};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    event::Event,
+    observer::Trigger,
+    system::{IntoSystem, ObserverSystem, ParamSet},
+    world::{OnAdd, OnInsert, OnRemove, World},
+};
+
+/// Lifecycle event of the component that entity system can be hooked to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookKind {
+    /// Component is added to the entity that didn't have it. See [`OnAdd`]
+    OnAdd,
+    /// Component is inserted to the entity, regardless of whether entity already had it. See [`OnInsert`]
+    OnInsert,
+    /// Component is about to be removed from the entity or the entity is about to be despawned.
+    /// See [`OnRemove`]
+    OnRemove,
+}
+
+/// Observer that runs `system` for the target of the lifecycle event `E` of the component `C`
+fn hook_observer<E: Event, C: Component, T: EntitySystem<In = (), Out = ()>>(
+    mut system: T,
+) -> impl ObserverSystem<E, C> {
+    IntoSystem::into_system(
+        move |trigger: Trigger<E, C>,
+              mut query: EntitySystemQuery<T>,
+              mut param: ParamSet<(T::Param,)>| {
+            if let Ok((entity, data)) = query.get_mut(trigger.entity()) {
+                system.run((), entity, data, param.p0());
+            }
+        },
+    )
+}
+
+/// Extension trait for [`World`] to run [`EntitySystem`]s
+/// on the lifecycle events of the components
+pub trait WorldEntitySystemHooksExt {
+    /// Registers `system` to be run for the entity every time the `kind` event
+    /// happens to the component `C` on that entity.
+    /// If entity doesn't match `Query<EntitySystem::Data, EntitySystem::Filter>`, nothing happens.
+    ///
+    /// System is run by the observer right when the event happens,
+    /// so for [`HookKind::OnRemove`] component is still present on the entity.
+    /// Deferred mutations of the system are applied on the next [`World::flush`].
+    ///
+    /// Returns the entity of the observer, despawn it to stop running the system.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Health(i32);
+    ///
+    /// #[derive(Component)]
+    /// struct MaxHealth(i32);
+    ///
+    /// fn fill_health(mut data: Data<(&mut Health, &MaxHealth)>) {
+    ///     let (health, max_health) = &mut data.item;
+    ///     health.0 = max_health.0;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.register_entity_system_hook::<Health, _, _>(HookKind::OnInsert, fill_health);
+    ///
+    /// let entity = world.spawn((Health(0), MaxHealth(100))).id();
+    /// assert_eq!(world.get::<Health>(entity).unwrap().0, 100);
+    /// ```
+    fn register_entity_system_hook<C: Component, Marker, T: IntoEntitySystem<(), (), Marker>>(
+        &mut self,
+        kind: HookKind,
+        system: T,
+    ) -> Entity;
+}
+
+impl WorldEntitySystemHooksExt for World {
+    fn register_entity_system_hook<C: Component, Marker, T: IntoEntitySystem<(), (), Marker>>(
+        &mut self,
+        kind: HookKind,
+        system: T,
+    ) -> Entity {
+        let system = system.into_entity_system();
+        let observer = match kind {
+            HookKind::OnAdd => self.observe(hook_observer::<OnAdd, C, _>(system)).id(),
+            HookKind::OnInsert => self.observe(hook_observer::<OnInsert, C, _>(system)).id(),
+            HookKind::OnRemove => self.observe(hook_observer::<OnRemove, C, _>(system)).id(),
+        };
+        self.flush();
+
+        observer
+    }
+}
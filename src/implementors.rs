@@ -1,12 +1,17 @@
 //! Built in implementations of [`EntitySystem`]
 
-use crate::{data_match::DataMatch, EntitySystem};
+use crate::{
+    data_match::DataMatch, into_entity_system::IntoEntitySystem, EntitySystem,
+    ReadOnlyEntitySystem,
+};
 use bevy_ecs::{
+    component::Component,
     entity::Entity,
-    query::QueryItem,
-    system::{lifetimeless::SQuery, ParamSet, SystemParamItem},
+    query::{Or, QueryFilter, QueryItem},
+    system::{lifetimeless::SQuery, ParamSet, SystemParam, SystemParamItem},
 };
 use bevy_entity_system_macros::IntoSystem;
+use std::marker::PhantomData;
 
 type SParamSet<P> = ParamSet<'static, 'static, P>;
 
@@ -30,7 +35,7 @@ type ESPipeParam<A, B> = SParamSet<(
 /// [`EntitySystem`] that pipes output of the first [`EntitySystem`] to the second [`EntitySystem`]
 /// and returns result of second entity system.
 /// This can be run for the entity if and only if both systems can be run for this entity.
-#[derive(IntoSystem)]
+#[derive(IntoSystem, Clone)]
 pub struct PipeEntitySystem<A: EntitySystem, B: EntitySystem<In = A::Out>>(A, B);
 
 impl<A: EntitySystem, B: EntitySystem<In = A::Out>> EntitySystem for PipeEntitySystem<A, B> {
@@ -44,18 +49,25 @@ impl<A: EntitySystem, B: EntitySystem<In = A::Out>> EntitySystem for PipeEntityS
     fn run(
         &mut self,
         input: Self::In,
-        entity: QueryItem<Self::Data>,
+        entity: Entity,
+        _data_value: QueryItem<Self::Data>,
         mut set: SystemParamItem<Self::Param>,
     ) -> Self::Out {
         let (mut query, param) = set.p0();
         let data_value = query.get_mut(entity).unwrap();
 
-        let result = A::run(&mut self.0, input, data_value, param);
+        let result = A::run(&mut self.0, input, entity, data_value, param);
 
         let (mut query, param) = set.p1();
         let data_value = query.get_mut(entity).unwrap();
 
-        B::run(&mut self.1, result, data_value, param)
+        B::run(&mut self.1, result, entity, data_value, param)
+    }
+
+    #[inline]
+    fn reset(&mut self, entity: Entity) {
+        self.0.reset(entity);
+        self.1.reset(entity);
     }
 }
 
@@ -72,7 +84,7 @@ pub fn entity_system_pipe<A: EntitySystem, B: EntitySystem<In = A::Out>>(
 /// If inner system can be run, returns `Ok` and result of the run.
 /// Otherwise returns `Err` with the supposed input for this system.
 /// Useful for [`piping`](PipeEntitySystem) systems
-#[derive(IntoSystem)]
+#[derive(IntoSystem, Clone)]
 pub struct OptionalEntitySystem<T: EntitySystem>(T);
 
 impl<T: EntitySystem> EntitySystem for OptionalEntitySystem<T> {
@@ -87,19 +99,24 @@ impl<T: EntitySystem> EntitySystem for OptionalEntitySystem<T> {
     fn run(
         &mut self,
         input: Self::In,
-        data_value: QueryItem<Self::Data>,
+        entity: Entity,
+        _data_value: QueryItem<Self::Data>,
         param_value: SystemParamItem<Self::Param>,
     ) -> Self::Out {
-        let entity = data_value;
         let (mut query, param) = param_value;
 
         let x = if let Ok(data) = query.get_mut(entity) {
-            Ok(self.0.run(input, data, param))
+            Ok(self.0.run(input, entity, data, param))
         } else {
             Err(input)
         };
         x
     }
+
+    #[inline]
+    fn reset(&mut self, entity: Entity) {
+        self.0.reset(entity);
+    }
 }
 
 /// See [`OptionalEntitySystem`]
@@ -108,6 +125,337 @@ pub fn optional<T: EntitySystem>(system: T) -> OptionalEntitySystem<T> {
     OptionalEntitySystem(system)
 }
 
+type ESOrElseFilter<A, B> = Or<(
+    (DataMatch<<A as EntitySystem>::Data>, <A as EntitySystem>::Filter),
+    (DataMatch<<B as EntitySystem>::Data>, <B as EntitySystem>::Filter),
+)>;
+type ESOrElseParam<A, B> = SParamSet<(
+    (
+        SQuery<<A as EntitySystem>::Data, <A as EntitySystem>::Filter>,
+        <A as EntitySystem>::Param,
+    ),
+    (
+        SQuery<<B as EntitySystem>::Data, <B as EntitySystem>::Filter>,
+        <B as EntitySystem>::Param,
+    ),
+)>;
+
+/// [`EntitySystem`] that runs the first [`EntitySystem`] if it can be run for the entity,
+/// otherwise runs the second [`EntitySystem`].
+/// This can be run for the entity if and only if any of the systems can be run for this entity.
+#[derive(IntoSystem, Clone)]
+pub struct OrElseEntitySystem<A: EntitySystem, B: EntitySystem<In = A::In, Out = A::Out>>(A, B);
+
+impl<A: EntitySystem, B: EntitySystem<In = A::In, Out = A::Out>> EntitySystem
+    for OrElseEntitySystem<A, B>
+{
+    type Data = Entity;
+    type Filter = ESOrElseFilter<A, B>;
+    type Param = ESOrElseParam<A, B>;
+
+    type In = A::In;
+    type Out = A::Out;
+
+    fn run(
+        &mut self,
+        input: Self::In,
+        entity: Entity,
+        _data_value: QueryItem<Self::Data>,
+        mut set: SystemParamItem<Self::Param>,
+    ) -> Self::Out {
+        {
+            let (mut query, param) = set.p0();
+            if let Ok(data_value) = query.get_mut(entity) {
+                return A::run(&mut self.0, input, entity, data_value, param);
+            };
+        }
+
+        let (mut query, param) = set.p1();
+        let data_value = query.get_mut(entity).unwrap();
+
+        B::run(&mut self.1, input, entity, data_value, param)
+    }
+
+    #[inline]
+    fn reset(&mut self, entity: Entity) {
+        self.0.reset(entity);
+        self.1.reset(entity);
+    }
+}
+
+/// See [`OrElseEntitySystem`]
+#[inline]
+pub fn or_else<A: EntitySystem, B: EntitySystem<In = A::In, Out = A::Out>>(
+    a: A,
+    b: B,
+) -> OrElseEntitySystem<A, B> {
+    OrElseEntitySystem(a, b)
+}
+
+type ESRunIfParam<T, C> = SParamSet<(
+    (
+        SQuery<<C as EntitySystem>::Data, <C as EntitySystem>::Filter>,
+        <C as EntitySystem>::Param,
+    ),
+    (
+        SQuery<<T as EntitySystem>::Data, <T as EntitySystem>::Filter>,
+        <T as EntitySystem>::Param,
+    ),
+)>;
+
+/// [`EntitySystem`] that runs the condition [`ReadOnlyEntitySystem`] for the entity and
+/// runs the inner [`EntitySystem`] only if condition returned `true`.
+/// Returns `None` if condition returned `false`.
+/// This can be run for the entity if and only if both systems can be run for this entity.
+#[derive(IntoSystem, Clone)]
+pub struct RunIfEntitySystem<T: EntitySystem, C: ReadOnlyEntitySystem<In = (), Out = bool>>(T, C);
+
+impl<T: EntitySystem, C: ReadOnlyEntitySystem<In = (), Out = bool>> EntitySystem
+    for RunIfEntitySystem<T, C>
+{
+    type Data = Entity;
+    type Filter = ESPipeFilter<T, C>;
+    type Param = ESRunIfParam<T, C>;
+
+    type In = T::In;
+    type Out = Option<T::Out>;
+
+    fn run(
+        &mut self,
+        input: Self::In,
+        entity: Entity,
+        _data_value: QueryItem<Self::Data>,
+        mut set: SystemParamItem<Self::Param>,
+    ) -> Self::Out {
+        let (mut query, param) = set.p0();
+        let data_value = query.get_mut(entity).unwrap();
+
+        if !C::run(&mut self.1, (), entity, data_value, param) {
+            return None;
+        }
+
+        let (mut query, param) = set.p1();
+        let data_value = query.get_mut(entity).unwrap();
+
+        Some(T::run(&mut self.0, input, entity, data_value, param))
+    }
+
+    #[inline]
+    fn reset(&mut self, entity: Entity) {
+        self.0.reset(entity);
+        self.1.reset(entity);
+    }
+}
+
+/// See [`RunIfEntitySystem`]
+#[inline]
+pub fn run_if_entity<T: EntitySystem, C: ReadOnlyEntitySystem<In = (), Out = bool>>(
+    system: T,
+    condition: C,
+) -> RunIfEntitySystem<T, C> {
+    RunIfEntitySystem(system, condition)
+}
+
+/// Tuple of [`EntitySystem`]s with the same input, that are run by [`JoinEntitySystem`].
+/// Implemented for tuples of up to 8 elements
+pub trait JoinedEntitySystems: Send + Sync + 'static {
+    /// Input of every system
+    type In: Clone;
+    /// Tuple of outputs of the systems
+    type Out;
+    /// Filter that matches entities that every system can be run on
+    type Filter: QueryFilter;
+    /// [`SystemParam`]'s of all the systems
+    type Param: SystemParam;
+
+    /// Runs every system for the `entity`, cloning the input for each of them.
+    ///
+    /// # Panics
+    /// If entity doesn't match [`Filter`](JoinedEntitySystems::Filter).
+    fn run(
+        &mut self,
+        input: Self::In,
+        entity: Entity,
+        param_value: SystemParamItem<Self::Param>,
+    ) -> Self::Out;
+
+    /// Resets every system for the `entity`. See [`EntitySystem::reset`]
+    fn reset(&mut self, entity: Entity);
+}
+
+/// Conversion of the tuple into [`JoinEntitySystem`].
+/// Implemented for tuples of [`IntoEntitySystem`]s with the same input
+pub trait IntoJoinEntitySystem<In, Marker>: Sized {
+    /// Converted tuple
+    type Systems: JoinedEntitySystems<In = In>;
+
+    /// Converts tuple into [`JoinedEntitySystems`]
+    fn into_systems(self) -> Self::Systems;
+
+    /// See [`JoinEntitySystem`]
+    #[inline]
+    fn join(self) -> JoinEntitySystem<Self::Systems> {
+        JoinEntitySystem(self.into_systems())
+    }
+}
+
+macro_rules! impl_joined_entity_systems {
+    ($(($index: tt, $p: ident, $system: ident, $marker: ident, $out: ident)),*) => {
+        impl<In: Clone, $($system: EntitySystem<In = In>),*> JoinedEntitySystems
+            for ($($system,)*)
+        {
+            type In = In;
+            type Out = ($($system::Out,)*);
+            type Filter = ($((DataMatch<$system::Data>, $system::Filter),)*);
+            type Param = SParamSet<($((SQuery<$system::Data, $system::Filter>, $system::Param),)*)>;
+
+            fn run(
+                &mut self,
+                input: Self::In,
+                entity: Entity,
+                mut set: SystemParamItem<Self::Param>,
+            ) -> Self::Out {
+                ($({
+                    let (mut query, param) = set.$p();
+                    let data_value = query.get_mut(entity).unwrap();
+                    self.$index.run(input.clone(), entity, data_value, param)
+                },)*)
+            }
+
+            #[inline]
+            fn reset(&mut self, entity: Entity) {
+                $(self.$index.reset(entity);)*
+            }
+        }
+
+        impl<In: Clone, $($system: IntoEntitySystem<In, $out, $marker>, $marker, $out),*>
+            IntoJoinEntitySystem<In, ($(($marker, $out),)*)> for ($($system,)*)
+        {
+            type Systems = ($($system::EntitySystem,)*);
+
+            #[inline]
+            fn into_systems(self) -> Self::Systems {
+                ($(self.$index.into_entity_system(),)*)
+            }
+        }
+    };
+}
+
+impl_joined_entity_systems!((0, p0, A, MA, OA));
+impl_joined_entity_systems!((0, p0, A, MA, OA), (1, p1, B, MB, OB));
+impl_joined_entity_systems!((0, p0, A, MA, OA), (1, p1, B, MB, OB), (2, p2, C, MC, OC));
+impl_joined_entity_systems!(
+    (0, p0, A, MA, OA),
+    (1, p1, B, MB, OB),
+    (2, p2, C, MC, OC),
+    (3, p3, D, MD, OD)
+);
+impl_joined_entity_systems!(
+    (0, p0, A, MA, OA),
+    (1, p1, B, MB, OB),
+    (2, p2, C, MC, OC),
+    (3, p3, D, MD, OD),
+    (4, p4, E, ME, OE)
+);
+impl_joined_entity_systems!(
+    (0, p0, A, MA, OA),
+    (1, p1, B, MB, OB),
+    (2, p2, C, MC, OC),
+    (3, p3, D, MD, OD),
+    (4, p4, E, ME, OE),
+    (5, p5, F, MF, OF)
+);
+impl_joined_entity_systems!(
+    (0, p0, A, MA, OA),
+    (1, p1, B, MB, OB),
+    (2, p2, C, MC, OC),
+    (3, p3, D, MD, OD),
+    (4, p4, E, ME, OE),
+    (5, p5, F, MF, OF),
+    (6, p6, G, MG, OG)
+);
+impl_joined_entity_systems!(
+    (0, p0, A, MA, OA),
+    (1, p1, B, MB, OB),
+    (2, p2, C, MC, OC),
+    (3, p3, D, MD, OD),
+    (4, p4, E, ME, OE),
+    (5, p5, F, MF, OF),
+    (6, p6, G, MG, OG),
+    (7, p7, H, MH, OH)
+);
+
+/// [`EntitySystem`] that runs all the [`EntitySystem`]s of the tuple with the cloned input
+/// and returns tuple of their outputs.
+/// This can be run for the entity if and only if all the systems can be run for this entity.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_entity_system::prelude::*;
+/// #[derive(Component)]
+/// struct Health(i32);
+///
+/// #[derive(Component)]
+/// struct Mana(i32);
+///
+/// fn health(data: Data<&Health>) -> i32 {
+///     data.0
+/// }
+///
+/// fn mana(data: Data<&Mana>) -> i32 {
+///     data.0
+/// }
+///
+/// let mut world = World::new();
+/// let entity = world.spawn((Health(10), Mana(5))).id();
+///
+/// assert_eq!(world.run_entity_system(entity, (health, mana).join(), ()), Ok((10, 5)));
+/// ```
+#[derive(IntoSystem, Clone)]
+pub struct JoinEntitySystem<T: JoinedEntitySystems>(T);
+
+impl<T: JoinedEntitySystems> EntitySystem for JoinEntitySystem<T> {
+    type Data = Entity;
+    type Filter = T::Filter;
+    type Param = T::Param;
+
+    type In = T::In;
+    type Out = T::Out;
+
+    #[inline]
+    fn run(
+        &mut self,
+        input: Self::In,
+        entity: Entity,
+        _data_value: QueryItem<Self::Data>,
+        param_value: SystemParamItem<Self::Param>,
+    ) -> Self::Out {
+        self.0.run(input, entity, param_value)
+    }
+
+    #[inline]
+    fn reset(&mut self, entity: Entity) {
+        self.0.reset(entity);
+    }
+}
+
+/// Joins two systems. See [`JoinEntitySystem`] and [`IntoJoinEntitySystem::join`]
+#[inline]
+pub fn join<
+    In: Clone,
+    AOut,
+    BOut,
+    AMarker,
+    BMarker,
+    A: IntoEntitySystem<In, AOut, AMarker>,
+    B: IntoEntitySystem<In, BOut, BMarker>,
+>(
+    a: A,
+    b: B,
+) -> JoinEntitySystem<(A::EntitySystem, B::EntitySystem)> {
+    (a, b).join()
+}
+
 /// Customize behavior of [`AdapterEntitySystem`]
 pub trait Adapt<S: EntitySystem>: Send + Sync + 'static {
     /// The input type for an [`AdapterEntitySystem`]
@@ -137,8 +485,61 @@ where
     }
 }
 
+/// [`Adapt`] that transforms the input of the system by applying `F` to it.
+/// See [`map_in`](crate::into_entity_system::IntoEntitySystem::map_in)
+pub struct MapIn<F, In: 'static>(F, PhantomData<fn(In)>);
+
+impl<F: Clone, In: 'static> Clone for MapIn<F, In> {
+    #[inline]
+    fn clone(&self) -> Self {
+        MapIn(self.0.clone(), PhantomData)
+    }
+}
+
+impl<F, In: 'static> MapIn<F, In> {
+    /// Constructor
+    #[inline]
+    pub fn new(func: F) -> Self {
+        MapIn(func, PhantomData)
+    }
+}
+
+impl<S, F, In> Adapt<S> for MapIn<F, In>
+where
+    S: EntitySystem,
+    F: Send + Sync + 'static + FnMut(In) -> S::In,
+    In: 'static,
+{
+    type In = In;
+    type Out = S::Out;
+
+    #[inline]
+    fn adapt(&mut self, input: In, run_system: impl FnOnce(S::In) -> S::Out) -> S::Out {
+        run_system((self.0)(input))
+    }
+}
+
+/// [`Adapt`] that runs the system with the clone of the stored input, so the system takes `()`.
+/// See [`with_input`](crate::into_entity_system::IntoEntitySystem::with_input)
+#[derive(Clone)]
+pub struct WithInput<T>(pub T);
+
+impl<S, T> Adapt<S> for WithInput<T>
+where
+    S: EntitySystem<In = T>,
+    T: Clone + Send + Sync + 'static,
+{
+    type In = ();
+    type Out = S::Out;
+
+    #[inline]
+    fn adapt(&mut self, _input: (), run_system: impl FnOnce(T) -> S::Out) -> S::Out {
+        run_system(self.0.clone())
+    }
+}
+
 /// An [`EntitySystem`] that takes the output of `T` and transforms it by applying `Func` to it.
-#[derive(IntoSystem)]
+#[derive(IntoSystem, Clone)]
 pub struct AdapterEntitySystem<T: EntitySystem, Func: Adapt<T>> {
     system: T,
     func: Func,
@@ -156,13 +557,19 @@ impl<T: EntitySystem, Func: Adapt<T>> EntitySystem for AdapterEntitySystem<T, Fu
     fn run(
         &mut self,
         input: Self::In,
+        entity: Entity,
         data_value: QueryItem<Self::Data>,
         param_value: SystemParamItem<Self::Param>,
     ) -> Self::Out {
         self.func.adapt(input, |input| {
-            self.system.run(input, data_value, param_value)
+            self.system.run(input, entity, data_value, param_value)
         })
     }
+
+    #[inline]
+    fn reset(&mut self, entity: Entity) {
+        self.system.reset(entity);
+    }
 }
 
 /// See [`AdapterEntitySystem`]
@@ -170,3 +577,105 @@ impl<T: EntitySystem, Func: Adapt<T>> EntitySystem for AdapterEntitySystem<T, Fu
 pub fn adapt<T: EntitySystem, A: Adapt<T>>(system: T, func: A) -> AdapterEntitySystem<T, A> {
     AdapterEntitySystem { system, func }
 }
+
+/// [`EntitySystem`] that can only be run for the entity if it matches both
+/// [`Filter`](EntitySystem::Filter) of `T` and `F`.
+/// Allows to reuse the same entity system with different filters, for example [`Changed`](bevy_ecs::query::Changed)
+#[derive(IntoSystem)]
+pub struct FilteredEntitySystem<T: EntitySystem, F: QueryFilter + 'static>(
+    T,
+    PhantomData<fn() -> F>,
+);
+
+impl<T: EntitySystem + Clone, F: QueryFilter + 'static> Clone for FilteredEntitySystem<T, F> {
+    #[inline]
+    fn clone(&self) -> Self {
+        FilteredEntitySystem(self.0.clone(), PhantomData)
+    }
+}
+
+impl<T: EntitySystem, F: QueryFilter + 'static> EntitySystem for FilteredEntitySystem<T, F> {
+    type Data = T::Data;
+    type Filter = (T::Filter, F);
+    type Param = T::Param;
+
+    type In = T::In;
+    type Out = T::Out;
+
+    #[inline]
+    fn run(
+        &mut self,
+        input: Self::In,
+        entity: Entity,
+        data_value: QueryItem<Self::Data>,
+        param_value: SystemParamItem<Self::Param>,
+    ) -> Self::Out {
+        self.0.run(input, entity, data_value, param_value)
+    }
+
+    #[inline]
+    fn reset(&mut self, entity: Entity) {
+        self.0.reset(entity);
+    }
+}
+
+/// See [`FilteredEntitySystem`]
+#[inline]
+pub fn filtered<T: EntitySystem, F: QueryFilter + 'static>(
+    system: T,
+) -> FilteredEntitySystem<T, F> {
+    FilteredEntitySystem(system, PhantomData)
+}
+
+/// [`EntitySystem`] that reads component `C` of the entity and passes it's clone as the input to `T`.
+/// Resulting system takes `()` as input.
+/// This can be run for the entity if and only if `T` can be run for this entity and entity has `C`.
+///
+/// `C` is added to the [`Data`](EntitySystem::Data) of the system as read-only,
+/// so `T` can't access `C` mutably.
+#[derive(IntoSystem)]
+pub struct InputFromEntitySystem<T: EntitySystem<In = C>, C: Component + Clone>(
+    T,
+    PhantomData<fn() -> C>,
+);
+
+impl<T: EntitySystem<In = C> + Clone, C: Component + Clone> Clone for InputFromEntitySystem<T, C> {
+    #[inline]
+    fn clone(&self) -> Self {
+        InputFromEntitySystem(self.0.clone(), PhantomData)
+    }
+}
+
+impl<T: EntitySystem<In = C>, C: Component + Clone> EntitySystem for InputFromEntitySystem<T, C> {
+    type Data = (T::Data, &'static C);
+    type Filter = T::Filter;
+    type Param = T::Param;
+
+    type In = ();
+    type Out = T::Out;
+
+    #[inline]
+    fn run(
+        &mut self,
+        _input: Self::In,
+        entity: Entity,
+        data_value: QueryItem<Self::Data>,
+        param_value: SystemParamItem<Self::Param>,
+    ) -> Self::Out {
+        let (data_value, input) = data_value;
+        self.0.run(input.clone(), entity, data_value, param_value)
+    }
+
+    #[inline]
+    fn reset(&mut self, entity: Entity) {
+        self.0.reset(entity);
+    }
+}
+
+/// See [`InputFromEntitySystem`]
+#[inline]
+pub fn input_from<T: EntitySystem<In = C>, C: Component + Clone>(
+    system: T,
+) -> InputFromEntitySystem<T, C> {
+    InputFromEntitySystem(system, PhantomData)
+}
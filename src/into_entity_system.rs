@@ -1,12 +1,34 @@
 //! Convenience in working with [`EntitySystem`]s
 
 use crate::{
-    implementors::{adapt, entity_system_pipe, optional},
-    marked_entity_system::{MarkedEntitySystem, MarkedEntitySystemRunner},
+    data_match::DataMatch,
+    dyn_entity_system::{BoxedEntitySystem, CachedEntitySystem},
+    implementors::{
+        adapt, entity_system_pipe, filtered, input_from, optional, or_else, run_if_entity,
+        FilteredEntitySystem, InputFromEntitySystem, MapIn, OrElseEntitySystem, RunIfEntitySystem,
+        WithInput,
+    },
+    into_system::TargetedEntitySystemParamFunction,
+    marked_entity_system::{InRef, MarkedEntitySystem, MarkedEntitySystemRunner},
+    parallel::ParallelParam,
     prelude::{AdapterEntitySystem, OptionalEntitySystem, PipeEntitySystem},
-    EntitySystem, ReadOnlyEntitySystem,
+    EntityMismatch, EntitySystem, ReadOnlyEntitySystem,
 };
-use bevy_ecs::system::{IntoSystem, ParamSet, Query, ReadOnlySystem, System};
+use bevy_ecs::{
+    bundle::Bundle,
+    component::Component,
+    entity::{Entity, EntityHashMap},
+    event::{Event, EventReader},
+    observer::Trigger,
+    query::{Added, Changed, QueryFilter, QueryState},
+    system::{
+        lifetimeless::SQuery, IntoSystem, ObserverSystem, ParamSet, Query, ReadOnlySystem,
+        ReadOnlySystemParam, System, SystemState,
+    },
+    world::World,
+};
+use bevy_utils::Parallel;
+use std::ops::ControlFlow;
 
 /// Glue trait for convenience of working with [`EntitySystem`]s.
 /// Everything that implements this trait can be converted to [`EntitySystem`].
@@ -50,17 +72,15 @@ pub trait IntoEntitySystem<In, Out, Marker>: Sized {
 
         IntoSystem::into_system(
             move |input: bevy_ecs::system::In<In>,
-                  mut query: Query<
-                <Self::EntitySystem as EntitySystem>::Data,
-                <Self::EntitySystem as EntitySystem>::Filter,
-            >,
+                  mut query: EntitySystemQuery<Self::EntitySystem>,
                   mut param: ParamSet<(<Self::EntitySystem as EntitySystem>::Param,)>| {
                 let mut output = T::default();
 
-                for data in query.iter_mut() {
+                for (entity, data) in query.iter_mut() {
                     let result = Self::EntitySystem::run(
                         &mut entity_system,
                         input.clone(),
+                        entity,
                         data,
                         param.p0(),
                     );
@@ -90,17 +110,15 @@ pub trait IntoEntitySystem<In, Out, Marker>: Sized {
 
         IntoSystem::into_system(
             move |input: bevy_ecs::system::In<In>,
-                  mut query: Query<
-                <Self::EntitySystem as EntitySystem>::Data,
-                <Self::EntitySystem as EntitySystem>::Filter,
-            >,
+                  mut query: EntitySystemQuery<Self::EntitySystem>,
                   mut param: ParamSet<(<Self::EntitySystem as EntitySystem>::Param,)>| {
                 let mut output = T::default();
 
-                for data in query.iter_mut() {
+                for (entity, data) in query.iter_mut() {
                     let result = Self::EntitySystem::run(
                         &mut entity_system,
                         input.clone(),
+                        entity,
                         data,
                         param.p0(),
                     );
@@ -112,6 +130,297 @@ pub trait IntoEntitySystem<In, Out, Marker>: Sized {
         )
     }
 
+    /// Converts [`EntitySystem`] to [`System`] that collects output of every entity system
+    /// that is being run into the map from the entity to the output of the system run on it.
+    /// This implementation will iterate over all the entities in the world
+    /// that can be run on by `Self::EntitySystem` every time the system is run
+    /// and runs `EntitySystem` for them. Input will be cloned for every run of `EntitySystem`.
+    ///
+    /// ```
+    /// # use bevy_ecs::{entity::EntityHashMap, prelude::*};
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Damage(i32);
+    ///
+    /// #[derive(Component)]
+    /// struct Health(i32);
+    ///
+    /// fn compute_damage(data: Data<&Damage>) -> i32 {
+    ///     data.0 * 2
+    /// }
+    ///
+    /// fn apply_damage(damage: In<EntityHashMap<i32>>, mut query: Query<&mut Health>) {
+    ///     for (entity, damage) in damage.iter() {
+    ///         if let Ok(mut health) = query.get_mut(*entity) {
+    ///             health.0 -= damage;
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let system = compute_damage.into_system_collect_entities().pipe(apply_damage);
+    /// # bevy_ecs::system::assert_is_system(system);
+    /// ```
+    fn into_system_collect_entities(self) -> impl System<In = In, Out = EntityHashMap<Out>>
+    where
+        In: Clone + 'static,
+        Out: 'static,
+    {
+        let mut entity_system = self.into_entity_system();
+
+        IntoSystem::into_system(
+            move |input: bevy_ecs::system::In<In>,
+                  mut query: EntitySystemQuery<Self::EntitySystem>,
+                  mut param: ParamSet<(<Self::EntitySystem as EntitySystem>::Param,)>| {
+                let mut output = EntityHashMap::default();
+
+                for (entity, data) in query.iter_mut() {
+                    let result = Self::EntitySystem::run(
+                        &mut entity_system,
+                        input.clone(),
+                        entity,
+                        data,
+                        param.p0(),
+                    );
+                    output.insert(entity, result);
+                }
+
+                output
+            },
+        )
+    }
+
+    /// Same as [`into_system_collect_entities`](IntoEntitySystem::into_system_collect_entities),
+    /// but collects pairs of the entity and the output of the system run on it into the [`Vec`]
+    /// in the order of the iteration.
+    fn into_system_collect_entities_vec(self) -> impl System<In = In, Out = Vec<(Entity, Out)>>
+    where
+        In: Clone + 'static,
+        Out: 'static,
+    {
+        let mut entity_system = self.into_entity_system();
+
+        IntoSystem::into_system(
+            move |input: bevy_ecs::system::In<In>,
+                  mut query: EntitySystemQuery<Self::EntitySystem>,
+                  mut param: ParamSet<(<Self::EntitySystem as EntitySystem>::Param,)>| {
+                let mut output = Vec::new();
+
+                for (entity, data) in query.iter_mut() {
+                    let result = Self::EntitySystem::run(
+                        &mut entity_system,
+                        input.clone(),
+                        entity,
+                        data,
+                        param.p0(),
+                    );
+                    output.push((entity, result));
+                }
+
+                output
+            },
+        )
+    }
+
+    /// Converts [`EntitySystem`] that takes [`InRef<T>`] to [`System`] that takes `T` as an input.
+    /// Works like [`into_system`](EntitySystemIntoSystem::into_system), but input is shared
+    /// between all the runs of entity system instead of being cloned, so `T` doesn't need to be [`Clone`].
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// struct Hits(Vec<Entity>);
+    ///
+    /// #[derive(Component)]
+    /// struct Health(i32);
+    ///
+    /// fn take_hits(hits: InRef<Hits>, mut data: Data<&mut Health>) {
+    ///     let count = hits.0.iter().filter(|hit| **hit == data.entity()).count();
+    ///     data.0 -= count as i32;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let entity = world.spawn(Health(10)).id();
+    ///
+    /// let system = world.register_system(take_hits.into_system_ref());
+    /// world
+    ///     .run_system_with_input(system, Hits(vec![entity, entity]))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(world.get::<Health>(entity).unwrap().0, 8);
+    /// ```
+    fn into_system_ref<T: 'static>(self) -> impl System<In = T, Out = ()>
+    where
+        Self::EntitySystem: EntitySystem<In = InRef<T>, Out = ()>,
+    {
+        let mut entity_system = self.into_entity_system();
+
+        IntoSystem::into_system(
+            move |bevy_ecs::system::In(input): bevy_ecs::system::In<T>,
+                  mut query: EntitySystemQuery<Self::EntitySystem>,
+                  mut param: ParamSet<(<Self::EntitySystem as EntitySystem>::Param,)>| {
+                let input = InRef::new(input);
+
+                for (entity, data) in query.iter_mut() {
+                    Self::EntitySystem::run(
+                        &mut entity_system,
+                        input.clone(),
+                        entity,
+                        data,
+                        param.p0(),
+                    );
+                }
+            },
+        )
+    }
+
+    /// Converts [`EntitySystem`] to [`System`] that takes the input for every entity separately
+    /// and runs entity system only for those entities, passing each of them it's own input.
+    ///
+    /// Outputs entities that entity system can't be run on.
+    ///
+    /// ```
+    /// # use bevy_ecs::{entity::EntityHashMap, prelude::*};
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Position(i32);
+    ///
+    /// fn move_by(In(offset): In<i32>, mut data: Data<&mut Position>) {
+    ///     data.0 += offset;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let first = world.spawn(Position(0)).id();
+    /// let second = world.spawn(Position(0)).id();
+    /// let empty = world.spawn_empty().id();
+    ///
+    /// let system = world.register_system(move_by.into_system_with_inputs());
+    /// let inputs = EntityHashMap::from_iter([(first, 1), (second, -1), (empty, 5)]);
+    ///
+    /// assert_eq!(
+    ///     world.run_system_with_input(system, inputs).unwrap(),
+    ///     vec![EntityMismatch(empty)]
+    /// );
+    /// assert_eq!(world.get::<Position>(first).unwrap().0, 1);
+    /// assert_eq!(world.get::<Position>(second).unwrap().0, -1);
+    /// ```
+    fn into_system_with_inputs(
+        self,
+    ) -> impl System<In = EntityHashMap<In>, Out = Vec<EntityMismatch>>
+    where
+        In: 'static,
+        Self::EntitySystem: EntitySystem<Out = ()>,
+    {
+        let mut entity_system = self.into_entity_system();
+
+        IntoSystem::into_system(
+            move |bevy_ecs::system::In(inputs): bevy_ecs::system::In<EntityHashMap<In>>,
+                  mut query: EntitySystemQuery<Self::EntitySystem>,
+                  mut param: ParamSet<(<Self::EntitySystem as EntitySystem>::Param,)>| {
+                let mut mismatches = Vec::new();
+
+                for (entity, input) in inputs {
+                    match query.get_mut(entity) {
+                        Ok((entity, data)) => Self::EntitySystem::run(
+                            &mut entity_system,
+                            input,
+                            entity,
+                            data,
+                            param.p0(),
+                        ),
+                        Err(_) => mismatches.push(EntityMismatch(entity)),
+                    }
+                }
+
+                mismatches
+            },
+        )
+    }
+
+    /// Converts [`EntitySystem`] to [`System`] that runs entity system for all the entities
+    /// in parallel and collects the outputs. Works like
+    /// [`into_system_with_output`](IntoEntitySystem::into_system_with_output),
+    /// but uses [`Query::par_iter_mut`].
+    ///
+    /// Every thread collects the outputs of the entity systems it runs into it's own value,
+    /// starting from [`Default`] and applying `func`. Then values of all the threads
+    /// are combined using `merge`. Order of the entities and threads isn't specified,
+    /// so `func` and `merge` should be associative.
+    ///
+    /// Entity system is cloned for every batch of entities and the clones are dropped after the run,
+    /// so changes to the state of the entity system itself, such as captures of the closure, are lost.
+    /// Entity systems that store progress between runs, like [`Sequence`](crate::behaviour_tree::Sequence),
+    /// don't implement [`Clone`] for that reason.
+    ///
+    /// [`Param`](EntitySystem::Param) has separate state for every thread, so only
+    /// read-only params are allowed. Params that store state between runs see different state
+    /// on every thread, for example every thread's [`EventReader`] reads the same events,
+    /// so such params shouldn't be used.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Count(i32);
+    ///
+    /// fn get_count(data: Data<&Count>) -> i32 {
+    ///     data.0
+    /// }
+    ///
+    /// let system = get_count.into_par_system_with_output(
+    ///     |sum: &mut i32, count| *sum += count,
+    ///     |sum, other| *sum += other,
+    /// );
+    ///
+    /// # bevy_ecs::system::assert_is_system(system);
+    /// ```
+    fn into_par_system_with_output<T: Default + Send + 'static>(
+        self,
+        func: impl Fn(&mut T, Out) + Send + Sync + 'static,
+        merge: impl Fn(&mut T, T) + Send + Sync + 'static,
+    ) -> impl System<In = In, Out = T>
+    where
+        In: Clone + Send + Sync + 'static,
+        Self::EntitySystem: Clone,
+        <Self::EntitySystem as EntitySystem>::Param: ReadOnlySystemParam,
+    {
+        let entity_system = self.into_entity_system();
+
+        IntoSystem::into_system(
+            move |input: bevy_ecs::system::In<In>,
+                  mut query: EntitySystemQuery<Self::EntitySystem>,
+                  param: ParallelParam<<Self::EntitySystem as EntitySystem>::Param>| {
+                let mut outputs = Parallel::<T>::default();
+
+                query.par_iter_mut().for_each_init(
+                    || {
+                        (
+                            entity_system.clone(),
+                            param.fetcher(),
+                            outputs.borrow_local_mut(),
+                        )
+                    },
+                    |(entity_system, param, output), (entity, data)| {
+                        let result = Self::EntitySystem::run(
+                            entity_system,
+                            input.clone(),
+                            entity,
+                            data,
+                            param.get(),
+                        );
+                        func(output, result);
+                    },
+                );
+
+                let mut output = T::default();
+                for value in outputs.iter_mut() {
+                    merge(&mut output, std::mem::take(value));
+                }
+
+                output
+            },
+        )
+    }
+
 
     /// See [`PipeEntitySystem`]
     #[inline]
@@ -131,11 +440,203 @@ pub trait IntoEntitySystem<In, Out, Marker>: Sized {
         adapt(self.into_entity_system(), func)
     }
 
+    /// Maps input of the system from the new type. See [`MapIn`]
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Position(f32);
+    ///
+    /// fn move_by(In(offset): In<f32>, mut data: Data<&mut Position>) {
+    ///     data.0 += offset;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let entity = world.spawn(Position(0.0)).id();
+    ///
+    /// let system = move_by.map_in(|steps: u32| steps as f32 * 0.5);
+    /// world.run_entity_system(entity, system, 4).unwrap();
+    ///
+    /// assert_eq!(world.get::<Position>(entity).unwrap().0, 2.0);
+    /// ```
+    #[inline]
+    fn map_in<F: Send + Sync + 'static + FnMut(I) -> In, I: 'static>(
+        self,
+        func: F,
+    ) -> AdapterEntitySystem<Self::EntitySystem, MapIn<F, I>> {
+        adapt(self.into_entity_system(), MapIn::new(func))
+    }
+
+    /// Runs the system with the clone of `input` every time, so resulting system takes `()`.
+    /// See [`WithInput`]
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Position(f32);
+    ///
+    /// fn move_by(In(offset): In<f32>, mut data: Data<&mut Position>) {
+    ///     data.0 += offset;
+    /// }
+    ///
+    /// # bevy_ecs::system::assert_is_system(move_by.with_input(1.5).into_system());
+    /// ```
+    #[inline]
+    fn with_input(self, input: In) -> AdapterEntitySystem<Self::EntitySystem, WithInput<In>>
+    where
+        In: Clone + Send + Sync + 'static,
+    {
+        adapt(self.into_entity_system(), WithInput(input))
+    }
+
+    /// Takes input of the system from the component `C` of the entity. See [`InputFromEntitySystem`]
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component, Clone)]
+    /// struct Intent(i32);
+    ///
+    /// #[derive(Component)]
+    /// struct Position(i32);
+    ///
+    /// fn read_intent(In(intent): In<Intent>, _: Data<()>) -> i32 {
+    ///     intent.0 * 2
+    /// }
+    ///
+    /// fn apply_move(In(offset): In<i32>, mut data: Data<&mut Position>) {
+    ///     data.0 += offset;
+    /// }
+    ///
+    /// # bevy_ecs::system::assert_is_system(read_intent.input_from::<Intent>().pipe(apply_move).into_system());
+    /// ```
+    #[inline]
+    fn input_from<C: Component + Clone>(self) -> InputFromEntitySystem<Self::EntitySystem, C>
+    where
+        Self::EntitySystem: EntitySystem<In = C>,
+    {
+        input_from(self.into_entity_system())
+    }
+
     /// See [`OptionalEntitySystem`]
     #[inline]
     fn optional(self) -> OptionalEntitySystem<Self::EntitySystem> {
         optional(self.into_entity_system())
     }
+
+    /// See [`OrElseEntitySystem`]
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Health(i32);
+    ///
+    /// #[derive(Component)]
+    /// struct Shield(i32);
+    ///
+    /// fn hit_shield(In(damage): In<i32>, mut data: Data<&mut Shield>) {
+    ///     data.0 -= damage;
+    /// }
+    ///
+    /// fn hit_health(In(damage): In<i32>, mut data: Data<&mut Health>) {
+    ///     data.0 -= damage;
+    /// }
+    ///
+    /// # bevy_ecs::system::assert_is_system(hit_shield.or_else(hit_health).into_system());
+    /// ```
+    #[inline]
+    fn or_else<BMarker, B: IntoEntitySystem<In, Out, BMarker>>(
+        self,
+        other: B,
+    ) -> OrElseEntitySystem<Self::EntitySystem, B::EntitySystem> {
+        or_else(self.into_entity_system(), other.into_entity_system())
+    }
+
+    /// Runs the system for the entity only if `condition` returns `true` for it.
+    /// See [`RunIfEntitySystem`]
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Health(i32);
+    ///
+    /// fn is_alive(data: Data<&Health>) -> bool {
+    ///     data.0 > 0
+    /// }
+    ///
+    /// fn regenerate(mut data: Data<&mut Health>) {
+    ///     data.0 += 1;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let alive = world.spawn(Health(5)).id();
+    /// let dead = world.spawn(Health(0)).id();
+    ///
+    /// let system = regenerate.run_if_entity(is_alive);
+    /// assert_eq!(world.run_entity_system(alive, system, ()), Ok(Some(())));
+    ///
+    /// let system = regenerate.run_if_entity(is_alive);
+    /// assert_eq!(world.run_entity_system(dead, system, ()), Ok(None));
+    /// ```
+    #[inline]
+    fn run_if_entity<
+        CMarker,
+        C: IntoEntitySystem<(), bool, CMarker, EntitySystem: ReadOnlyEntitySystem>,
+    >(
+        self,
+        condition: C,
+    ) -> RunIfEntitySystem<Self::EntitySystem, C::EntitySystem> {
+        run_if_entity(self.into_entity_system(), condition.into_entity_system())
+    }
+
+    /// Adds `F` to the [`Filter`](EntitySystem::Filter) of the system. See [`FilteredEntitySystem`]
+    #[inline]
+    fn with_filter<F: QueryFilter + 'static>(self) -> FilteredEntitySystem<Self::EntitySystem, F> {
+        filtered(self.into_entity_system())
+    }
+
+    /// Converts entity system into [`BoxedEntitySystem`], erasing it's type.
+    /// State of the system is initialized on the first run
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Health(i32);
+    ///
+    /// #[derive(Component)]
+    /// struct Poisoned;
+    ///
+    /// fn regenerate(mut data: Data<&mut Health>) {
+    ///     data.0 += 1;
+    /// }
+    ///
+    /// fn poison(mut data: Data<&mut Health, With<Poisoned>>) {
+    ///     data.0 -= 2;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let entity = world.spawn((Health(10), Poisoned)).id();
+    ///
+    /// let mut systems: Vec<BoxedEntitySystem> = vec![regenerate.boxed(), poison.boxed()];
+    /// for system in &mut systems {
+    ///     system.run((), entity, &mut world).unwrap();
+    /// }
+    ///
+    /// assert_eq!(world.get::<Health>(entity).unwrap().0, 9);
+    /// ```
+    #[inline]
+    fn boxed(self) -> BoxedEntitySystem<In, Out>
+    where
+        In: 'static,
+        Out: 'static,
+    {
+        Box::new(CachedEntitySystem::new(self.into_entity_system()))
+    }
 }
 
 impl<T: EntitySystem> IntoEntitySystem<T::In, T::Out, ()> for T {
@@ -167,7 +668,8 @@ pub trait EntitySystemIntoSystem<In: Clone + 'static, Marker>:
     ///
     /// Using this implementation will output the system that iterates over all the entities in the world
     /// that can be run on by `<Self as IntoEntitySystem>::EntitySystem` every time the system is run.
-    /// Input to the system will be cloned for every run of entity system
+    /// Input to the system will be cloned for every run of entity system.
+    /// Use [`into_system_ref`](IntoEntitySystem::into_system_ref) for input that isn't [`Clone`]
     fn into_system(self) -> impl System<In = In, Out = ()>;
 
     /// Turns [`ReadOnlyEntitySystem`] into [`ReadOnlySystem`]
@@ -177,8 +679,185 @@ pub trait EntitySystemIntoSystem<In: Clone + 'static, Marker>:
     /// Input to the system will be cloned for every run of entity system
     fn into_read_only_system(self) -> impl ReadOnlySystem<In = In, Out = ()>
         where Self::EntitySystem: ReadOnlyEntitySystem;
+
+    /// Turns [`EntitySystem`] into exclusive [`System`] that stores separate state
+    /// of [`Param`](EntitySystem::Param) for every entity.
+    ///
+    /// Using this implementation will output the system that iterates over all the entities in the world
+    /// that can be run on by `<Self as IntoEntitySystem>::EntitySystem` every time the system is run.
+    /// State is created when entity first matches `Query<Data, Filter>` and is dropped
+    /// when entity is despawned or stops matching it, so `Local`, `EventReader` and other
+    /// stateful `SystemParam`s work as expected.
+    /// Deferred mutations (e.g. `Commands`) are applied right after each run of entity system.
+    /// Input to the system will be cloned for every run of entity system
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Count(i32);
+    ///
+    /// // Every entity has it's own `Local`
+    /// fn count_runs(mut data: Data<&mut Count>, mut runs: Local<i32>) {
+    ///     *runs += 1;
+    ///     data.0 = *runs;
+    /// }
+    ///
+    /// # bevy_ecs::system::assert_is_system(count_runs.into_stateful_system());
+    /// ```
+    fn into_stateful_system(self) -> impl System<In = In, Out = ()>;
+
+    /// Turns [`EntitySystem`] into [`System`] that only runs on entities explicitly specified
+    /// in the [`EntityTargets<L>`](crate::into_system::EntityTargets) resource. See [`TargetedEntitySystemParamFunction`].
+    ///
+    /// Entities that can't be run on are reported as an output of the system,
+    /// despawned entities are removed from the targets instead.
+    /// Input to the system will be cloned for every run of entity system
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Count(i32);
+    ///
+    /// struct Player;
+    ///
+    /// fn increment(mut data: Data<&mut Count>) {
+    ///     data.0 += 1;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let entity = world.spawn(Count(0)).id();
+    ///
+    /// let mut targets = EntityTargets::<Player>::default();
+    /// targets.insert(entity);
+    /// world.insert_resource(targets);
+    ///
+    /// let system = world.register_system(increment.into_targeted_system::<Player>());
+    /// assert!(world.run_system(system).unwrap().is_empty());
+    /// ```
+    fn into_targeted_system<L: 'static>(self) -> impl System<In = In, Out = Vec<EntityMismatch>>;
+
+    /// Turns [`EntitySystem`] into [`System`] that only runs on entities which component `C`
+    /// was changed since the last run of the system.
+    ///
+    /// Same as `into_system` on the entity system with [`Changed<C>`] added to it's filter,
+    /// so the same entity system can be used both every frame and reactively.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Health(i32);
+    ///
+    /// fn clamp_health(mut data: Data<&mut Health>) {
+    ///     data.0 = data.0.clamp(0, 100);
+    /// }
+    ///
+    /// # bevy_ecs::system::assert_is_system(clamp_health.into_system());
+    /// # bevy_ecs::system::assert_is_system(clamp_health.into_system_on_changed::<Health>());
+    /// ```
+    fn into_system_on_changed<C: Component>(self) -> impl System<In = In, Out = ()>;
+
+    /// Turns [`EntitySystem`] into [`System`] that only runs on entities which component `C`
+    /// was added since the last run of the system.
+    ///
+    /// Same as `into_system` on the entity system with [`Added<C>`] added to it's filter.
+    /// See [`into_system_on_changed`](EntitySystemIntoSystem::into_system_on_changed)
+    fn into_system_on_added<C: Component>(self) -> impl System<In = In, Out = ()>;
+
+    /// Turns [`EntitySystem`] into [`ObserverSystem`] that runs entity system
+    /// for the target of the [`Trigger`], passing the clone of the event as an input.
+    ///
+    /// If trigger doesn't have a target or target doesn't match
+    /// `Query<EntitySystem::Data, EntitySystem::Filter>`, it's ignored.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Event, Clone)]
+    /// struct Damage(i32);
+    ///
+    /// #[derive(Component)]
+    /// struct Health(i32);
+    ///
+    /// fn take_damage(In(damage): In<Damage>, mut data: Data<&mut Health>) {
+    ///     data.0 -= damage.0;
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let entity = world.spawn(Health(10)).id();
+    ///
+    /// world.observe(take_damage.into_observer::<()>());
+    /// world.flush();
+    /// world.trigger_targets(Damage(3), entity);
+    ///
+    /// assert_eq!(world.get::<Health>(entity).unwrap().0, 7);
+    /// ```
+    fn into_observer<B: Bundle>(self) -> impl ObserverSystem<In, B>
+    where
+        In: Event;
+
+    /// Turns [`EntitySystem`] into [`System`] that reads all the events of type `In`
+    /// since the last run and runs entity system for the entity returned by `target`,
+    /// passing the clone of the event as an input.
+    ///
+    /// Events which targets don't match `Query<EntitySystem::Data, EntitySystem::Filter>` are ignored.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Event, Clone)]
+    /// struct Damage {
+    ///     target: Entity,
+    ///     amount: i32,
+    /// }
+    ///
+    /// #[derive(Component)]
+    /// struct Health(i32);
+    ///
+    /// fn take_damage(In(damage): In<Damage>, mut data: Data<&mut Health>) {
+    ///     data.0 -= damage.amount;
+    /// }
+    ///
+    /// # bevy_ecs::system::assert_is_system(take_damage.into_event_system(|e: &Damage| e.target));
+    /// ```
+    fn into_event_system<F: Fn(&In) -> Entity + Send + Sync + 'static>(
+        self,
+        target: F,
+    ) -> impl System<In = (), Out = ()>
+    where
+        In: Event;
+
+    /// Turns [`EntitySystem`] into [`System`] that runs entity system for all the entities in parallel.
+    /// See [`into_par_system_with_output`](IntoEntitySystem::into_par_system_with_output)
+    fn into_par_system(self) -> impl System<In = In, Out = ()>
+    where
+        In: Send + Sync,
+        Self::EntitySystem: Clone,
+        <Self::EntitySystem as EntitySystem>::Param: ReadOnlySystemParam;
 }
 
+pub(crate) type EntitySystemQuery<'w, 's, T> =
+    Query<'w, 's, (Entity, <T as EntitySystem>::Data), <T as EntitySystem>::Filter>;
+
+type EntityMatchQueryState<T> = QueryState<
+    Entity,
+    (
+        DataMatch<<T as EntitySystem>::Data>,
+        <T as EntitySystem>::Filter,
+    ),
+>;
+
+type EntityDataQueryState<T> = QueryState<<T as EntitySystem>::Data, <T as EntitySystem>::Filter>;
+
+type EntityParamState<T> = SystemState<<T as EntitySystem>::Param>;
+
+type EntitySystemState<T> = SystemState<(
+    SQuery<<T as EntitySystem>::Data, <T as EntitySystem>::Filter>,
+    <T as EntitySystem>::Param,
+)>;
+
 impl<In: Clone + 'static, Marker, T: IntoEntitySystem<In, (), Marker>>
     EntitySystemIntoSystem<In, Marker> for T
 {
@@ -187,13 +866,16 @@ impl<In: Clone + 'static, Marker, T: IntoEntitySystem<In, (), Marker>>
 
         IntoSystem::into_system(
             move |input: bevy_ecs::system::In<In>,
-                  mut query: Query<
-                <T::EntitySystem as EntitySystem>::Data,
-                <T::EntitySystem as EntitySystem>::Filter,
-            >,
+                  mut query: EntitySystemQuery<T::EntitySystem>,
                   mut param: ParamSet<(<T::EntitySystem as EntitySystem>::Param,)>| {
-                for data in query.iter_mut() {
-                    T::EntitySystem::run(&mut entity_system, input.clone(), data, param.p0());
+                for (entity, data) in query.iter_mut() {
+                    T::EntitySystem::run(
+                        &mut entity_system,
+                        input.clone(),
+                        entity,
+                        data,
+                        param.p0(),
+                    );
                 }
             },
         )
@@ -205,16 +887,222 @@ impl<In: Clone + 'static, Marker, T: IntoEntitySystem<In, (), Marker>>
 
         IntoSystem::into_system(
             move |input: bevy_ecs::system::In<In>,
-                  mut query: Query<
-                <T::EntitySystem as EntitySystem>::Data,
-                <T::EntitySystem as EntitySystem>::Filter,
-            >,
+                  mut query: EntitySystemQuery<T::EntitySystem>,
+                  mut param: ParamSet<(<T::EntitySystem as EntitySystem>::Param,)>| {
+                for (entity, data) in query.iter_mut() {
+                    T::EntitySystem::run(
+                        &mut entity_system,
+                        input.clone(),
+                        entity,
+                        data,
+                        param.p0(),
+                    );
+                }
+            },
+        )
+    }
+
+    fn into_stateful_system(self) -> impl System<In = In, Out = ()> {
+        let mut entity_system = self.into_entity_system();
+        let mut states = EntityHashMap::<EntityParamState<T::EntitySystem>>::default();
+        let mut validated = false;
+
+        IntoSystem::into_system(
+            move |input: bevy_ecs::system::In<In>,
+                  world: &mut World,
+                  query: &mut EntityMatchQueryState<T::EntitySystem>,
+                  data_query: &mut EntityDataQueryState<T::EntitySystem>| {
+                if !validated {
+                    // Panics if `Data` conflicts with `Param`, data and params
+                    // are fetched from the separate states below
+                    let _ = EntitySystemState::<T::EntitySystem>::new(world);
+                    validated = true;
+                }
+
+                let entities: Vec<Entity> = query.iter(world).collect();
+
+                // Drop the state of entities that were despawned or can't be run on anymore
+                states.retain(|&entity, _| {
+                    world.get_entity(entity).is_some_and(|entity_ref| {
+                        let archetype = entity_ref.archetype();
+                        query.matches_component_set(&|id| archetype.contains(id))
+                    })
+                });
+
+                for entity in entities {
+                    let state = states
+                        .entry(entity)
+                        .or_insert_with(|| SystemState::new(world));
+
+                    let world_cell = world.as_unsafe_world_cell();
+                    state.update_archetypes_unsafe_world_cell(world_cell);
+
+                    // SAFETY: World is borrowed mutably, so there is no other access to it.
+                    // Access of `Data` doesn't conflict with access of `Param`, it's validated above.
+                    if let Ok(data) = unsafe { data_query.get_unchecked(world_cell, entity) } {
+                        // SAFETY: Same as above, state was created with this world
+                        let param = unsafe { state.get_unchecked_manual(world_cell) };
+                        T::EntitySystem::run(&mut entity_system, input.clone(), entity, data, param);
+                    }
+
+                    state.apply(world);
+                }
+            },
+        )
+    }
+    #[inline]
+    fn into_targeted_system<L: 'static>(self) -> impl System<In = In, Out = Vec<EntityMismatch>> {
+        IntoSystem::into_system(TargetedEntitySystemParamFunction::<L, _>::new(
+            self.into_entity_system(),
+        ))
+    }
+    #[inline]
+    fn into_system_on_changed<C: Component>(self) -> impl System<In = In, Out = ()> {
+        EntitySystemIntoSystem::into_system(self.with_filter::<Changed<C>>())
+    }
+
+    #[inline]
+    fn into_system_on_added<C: Component>(self) -> impl System<In = In, Out = ()> {
+        EntitySystemIntoSystem::into_system(self.with_filter::<Added<C>>())
+    }
+
+    fn into_observer<B: Bundle>(self) -> impl ObserverSystem<In, B>
+    where
+        In: Event,
+    {
+        let mut entity_system = self.into_entity_system();
+
+        IntoSystem::into_system(
+            move |trigger: Trigger<In, B>,
+                  mut query: EntitySystemQuery<T::EntitySystem>,
+                  mut param: ParamSet<(<T::EntitySystem as EntitySystem>::Param,)>| {
+                if let Ok((entity, data)) = query.get_mut(trigger.entity()) {
+                    T::EntitySystem::run(
+                        &mut entity_system,
+                        trigger.event().clone(),
+                        entity,
+                        data,
+                        param.p0(),
+                    );
+                }
+            },
+        )
+    }
+
+    fn into_event_system<F: Fn(&In) -> Entity + Send + Sync + 'static>(
+        self,
+        target: F,
+    ) -> impl System<In = (), Out = ()>
+    where
+        In: Event,
+    {
+        let mut entity_system = self.into_entity_system();
+
+        IntoSystem::into_system(
+            move |mut events: EventReader<In>,
+                  mut query: EntitySystemQuery<T::EntitySystem>,
                   mut param: ParamSet<(<T::EntitySystem as EntitySystem>::Param,)>| {
-                for data in query.iter_mut() {
-                    T::EntitySystem::run(&mut entity_system, input.clone(), data, param.p0());
+                for event in events.read() {
+                    if let Ok((entity, data)) = query.get_mut(target(event)) {
+                        T::EntitySystem::run(
+                            &mut entity_system,
+                            event.clone(),
+                            entity,
+                            data,
+                            param.p0(),
+                        );
+                    }
                 }
             },
         )
     }
 
+    #[inline]
+    fn into_par_system(self) -> impl System<In = In, Out = ()>
+    where
+        In: Send + Sync,
+        Self::EntitySystem: Clone,
+        <Self::EntitySystem as EntitySystem>::Param: ReadOnlySystemParam,
+    {
+        self.into_par_system_with_output(|_: &mut (), _| {}, |_, _| {})
+    }
+}
+
+/// Trait exists because `=` in where clauses isn't allowed
+pub trait EntitySystemIntoSystemUntil<In: Clone + 'static, B: 'static, C, Marker>:
+    IntoEntitySystem<In, ControlFlow<B, C>, Marker>
+{
+    /// Turns [`EntitySystem`] that returns [`ControlFlow`] into [`System`]
+    /// that stops iteration as soon as entity system returns [`ControlFlow::Break`].
+    ///
+    /// Using this implementation will output the system that iterates over all the entities in the world
+    /// that can be run on by `<Self as IntoEntitySystem>::EntitySystem` every time the system is run,
+    /// until one of the runs breaks. Outputs value of the break or `None` if all runs continued.
+    /// Input to the system will be cloned for every run of entity system
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// # use std::ops::ControlFlow;
+    /// #[derive(Component)]
+    /// struct Position(f32);
+    ///
+    /// #[derive(Component)]
+    /// struct Enemy;
+    ///
+    /// fn find_in_range(
+    ///     player: In<f32>,
+    ///     data: Data<&Position, With<Enemy>>,
+    /// ) -> ControlFlow<Entity> {
+    ///     if (data.0 - *player).abs() < 10.0 {
+    ///         ControlFlow::Break(data.entity())
+    ///     } else {
+    ///         ControlFlow::Continue(())
+    ///     }
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.spawn((Position(100.0), Enemy));
+    /// let enemy = world.spawn((Position(5.0), Enemy)).id();
+    ///
+    /// let system = world.register_system(find_in_range.into_system_until());
+    /// assert_eq!(world.run_system_with_input(system, 0.0).unwrap(), Some(enemy));
+    /// assert_eq!(world.run_system_with_input(system, 50.0).unwrap(), None);
+    /// ```
+    fn into_system_until(self) -> impl System<In = In, Out = Option<B>>;
+}
+
+impl<
+        In: Clone + 'static,
+        B: 'static,
+        C,
+        Marker,
+        T: IntoEntitySystem<In, ControlFlow<B, C>, Marker>,
+    > EntitySystemIntoSystemUntil<In, B, C, Marker> for T
+{
+    fn into_system_until(self) -> impl System<In = In, Out = Option<B>> {
+        let mut entity_system = self.into_entity_system();
+
+        IntoSystem::into_system(
+            move |input: bevy_ecs::system::In<In>,
+                  mut query: EntitySystemQuery<T::EntitySystem>,
+                  mut param: ParamSet<(<T::EntitySystem as EntitySystem>::Param,)>| {
+                for (entity, data) in query.iter_mut() {
+                    let result = T::EntitySystem::run(
+                        &mut entity_system,
+                        input.clone(),
+                        entity,
+                        data,
+                        param.p0(),
+                    );
+
+                    if let ControlFlow::Break(value) = result {
+                        return Some(value);
+                    }
+                }
+
+                None
+            },
+        )
+    }
 }
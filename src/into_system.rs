@@ -1,7 +1,15 @@
 //! Implementation of [`IntoSystem`]
 
-use crate::EntitySystem;
-use bevy_ecs::system::{lifetimeless::SQuery, ParamSet, SystemParamFunction, SystemParamItem};
+use crate::{EntityMismatch, EntitySystem};
+use bevy_ecs::{
+    entity::{Entity, EntityHashSet},
+    query::QueryEntityError,
+    system::{
+        lifetimeless::{SQuery, SResMut},
+        ParamSet, Resource, SystemParamFunction, SystemParamItem,
+    },
+};
+use std::marker::PhantomData;
 
 pub use bevy_entity_system_macros::IntoSystem;
 
@@ -19,15 +27,123 @@ impl<T: EntitySystem<In: Clone, Out = ()>> SystemParamFunction<IsEntitySystem>
     type In = T::In;
     type Out = ();
     type Param = (
-        SQuery<T::Data, T::Filter>,
+        SQuery<(Entity, T::Data), T::Filter>,
         ParamSet<'static, 'static, (T::Param,)>,
     );
 
     fn run(&mut self, input: Self::In, param_value: SystemParamItem<Self::Param>) -> Self::Out {
         let (mut query, mut param) = param_value;
 
-        for data in query.iter_mut() {
-            T::run(&mut self.0, input.clone(), data, param.p0());
+        for (entity, data) in query.iter_mut() {
+            T::run(&mut self.0, input.clone(), entity, data, param.p0());
+        }
+    }
+}
+
+/// [`Resource`] that holds entities that [`TargetedEntitySystemParamFunction`]s
+/// with the label `L` are allowed to run on.
+///
+/// Entities can be added and removed at any time, changes will be picked up
+/// on the next run of the system. Despawned entities are removed by the system when it runs.
+#[derive(Resource)]
+pub struct EntityTargets<L: 'static> {
+    entities: EntityHashSet,
+    marker: PhantomData<fn() -> L>,
+}
+
+impl<L: 'static> Default for EntityTargets<L> {
+    fn default() -> Self {
+        EntityTargets {
+            entities: EntityHashSet::default(),
+            marker: PhantomData,
         }
     }
 }
+
+impl<L: 'static> EntityTargets<L> {
+    /// Allows systems to run on the `entity`.
+    /// Returns `false` if entity was already present
+    #[inline]
+    pub fn insert(&mut self, entity: Entity) -> bool {
+        self.entities.insert(entity)
+    }
+
+    /// Disallows systems to run on the `entity`.
+    /// Returns `false` if entity wasn't present
+    #[inline]
+    pub fn remove(&mut self, entity: Entity) -> bool {
+        self.entities.remove(&entity)
+    }
+
+    /// Returns `true` if systems are allowed to run on the `entity`
+    #[inline]
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.entities.contains(&entity)
+    }
+
+    /// Removes all the entities
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entities.clear();
+    }
+
+    /// Iterates over all the entities systems are allowed to run on
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Entity> + '_ {
+        self.entities.iter().copied()
+    }
+}
+
+/// [`SystemParamFunction`], every time it's run, it iterates over all the entities in
+/// [`EntityTargets<L>`] resource and runs `T` for them. Input will be cloned for every run of `T`.
+///
+/// Outputs entities that exist, but `T` can't be run on. Entities that were despawned are removed
+/// from the resource instead. If the resource doesn't exist, system doesn't do anything.
+pub struct TargetedEntitySystemParamFunction<L: 'static, T: EntitySystem<In: Clone, Out = ()>>(
+    pub T,
+    PhantomData<fn() -> L>,
+);
+
+impl<L: 'static, T: EntitySystem<In: Clone, Out = ()>> TargetedEntitySystemParamFunction<L, T> {
+    /// Constructor
+    #[inline]
+    pub fn new(system: T) -> Self {
+        TargetedEntitySystemParamFunction(system, PhantomData)
+    }
+}
+
+impl<L: 'static, T: EntitySystem<In: Clone, Out = ()>> SystemParamFunction<IsEntitySystem>
+    for TargetedEntitySystemParamFunction<L, T>
+{
+    type In = T::In;
+    type Out = Vec<EntityMismatch>;
+    type Param = (
+        Option<SResMut<EntityTargets<L>>>,
+        SQuery<T::Data, T::Filter>,
+        ParamSet<'static, 'static, (T::Param,)>,
+    );
+
+    fn run(&mut self, input: Self::In, param_value: SystemParamItem<Self::Param>) -> Self::Out {
+        let (targets, mut query, mut param) = param_value;
+        let mut mismatches = Vec::new();
+
+        let Some(mut targets) = targets else {
+            return mismatches;
+        };
+
+        let mut despawned = Vec::new();
+        for entity in targets.iter() {
+            match query.get_mut(entity) {
+                Ok(data) => T::run(&mut self.0, input.clone(), entity, data, param.p0()),
+                Err(QueryEntityError::NoSuchEntity(_)) => despawned.push(entity),
+                Err(_) => mismatches.push(EntityMismatch(entity)),
+            }
+        }
+
+        for entity in despawned {
+            targets.remove(entity);
+        }
+
+        mismatches
+    }
+}
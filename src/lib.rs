@@ -2,16 +2,13 @@
 //! This crate provides easy to use way to make systems that operate on a single entity.
 //!
 //! # Warning
-//! Currently there is a bug, individual entity systems don't store their state.
-//! State is being shared across all entities that are being iterated by the system that resulted from `into_system` call.
+//! Systems that resulted from `into_system` call don't store state of individual entity systems.
+//! State is being shared across all entities that are being iterated by the system.
 //! That means that `Local`, `EntityReader` and other `SystemParam`'s, that
 //! rely on it's state property to be preserved between system runs, won't work correctly.
-//! It is a huge footgun, so the work on fixing it is being done.
 //!
-//! After fix, the API will change, user would need to explicitly specify the entities this system is allowed to run on.
-//! That means, you most probably would need to be able to mutate systems at 
-//! runtime - something that bevy currently doesn't support. 
-//! I also work on a crate that will be able to provide you with such functionality - it is not published yet.
+//! Use `into_stateful_system` instead if you need such params,
+//! it stores separate state for every entity at the cost of being exclusive system.
 //!
 //! # Example
 //!  
@@ -43,18 +40,29 @@
 extern crate self as bevy_entity_system;
 
 use bevy_ecs::{
+    entity::Entity,
     query::{QueryData, QueryFilter, QueryItem, ReadOnlyQueryData},
     system::{ReadOnlySystemParam, SystemParam, SystemParamItem},
 };
+use std::fmt;
 
+pub mod behaviour;
+pub mod behaviour_tree;
 pub mod data_match;
+pub mod dyn_entity_system;
+pub mod entity_param;
+pub mod hooks;
 pub mod implementors;
 pub mod into_entity_system;
 pub mod into_system;
 pub mod marked_entity_system;
+mod parallel;
+pub mod state_machine;
+pub mod system_registry;
+pub mod utility;
 
 /// Trait implemented for all functions that can be used as [`System`](bevy_ecs::system::System)s
-/// and operate on a single [`Entity`](bevy_ecs::entity::Entity).
+/// and operate on a single [`Entity`].
 /// Such system can only be run for entities that match it's [`Data`](EntitySystem::Data) and [`Filter`](EntitySystem::Filter)
 ///
 /// Every entity system that is function that has [`Data`](crate::marked_entity_system::Data)
@@ -85,16 +93,13 @@ pub mod marked_entity_system;
 /// ```
 ///
 /// # Warning
-/// Currently there is a bug, individual entity systems don't store their state.
-/// State is being shared across all entities that are being iterated by the system that resulted from `into_system` call.
+/// Systems that resulted from `into_system` call don't store state of individual entity systems.
+/// State is being shared across all entities that are being iterated by the system.
 /// That means that `Local`, `EntityReader` and other `SystemParam`'s, that
 /// rely on it's state property to be preserved between system runs, won't work correctly.
-/// It is a huge footgun, so the work on fixing it is being done.
 ///
-/// After fix, the API will change, user would need to explicitly specify the entities this system is allowed to run on.
-/// That means, you most probably would need to be able to mutate systems at 
-/// runtime - something that bevy currently doesn't support. 
-/// I also work on a crate that will be able to provide you with such functionality - it is not published yet.
+/// Use `into_stateful_system` instead if you need such params,
+/// it stores separate state for every entity at the cost of being exclusive system.
 /// 
 /// # Custom implementation
 /// If you have a custom implementation of the trait, it's highly recommended to also
@@ -117,13 +122,19 @@ pub trait EntitySystem: Send + Sync + 'static {
     /// Output of the system
     type Out;
 
-    /// Executes this system once.
+    /// Executes this system once for the `entity`.
     fn run(
         &mut self,
         input: Self::In,
+        entity: Entity,
         data_value: QueryItem<Self::Data>,
         param_value: SystemParamItem<Self::Param>,
     ) -> Self::Out;
+
+    /// Clears the progress that system stores for the `entity`, so the next run for it starts over.
+    /// Used by [`behaviour_tree`] nodes to halt the children they abort. Does nothing by default
+    #[inline]
+    fn reset(&mut self, _entity: Entity) {}
 }
 
 /// Implemented for [`EntitySystem`]s that only read data from the world
@@ -134,13 +145,47 @@ pub trait ReadOnlyEntitySystem:
 
 impl<T: EntitySystem<Data: ReadOnlyQueryData, Param: ReadOnlySystemParam>> ReadOnlyEntitySystem for T {}
 
+/// Error that is reported when [`EntitySystem`] was requested to run on the entity
+/// that doesn't exist or doesn't match it's [`Data`](EntitySystem::Data) and [`Filter`](EntitySystem::Filter)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EntityMismatch(pub Entity);
+
+impl fmt::Display for EntityMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "entity {:?} doesn't match `Data` and `Filter` of the entity system",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for EntityMismatch {}
+
 /// Prelude module
 pub mod prelude {
     pub use crate::{
-        implementors::{AdapterEntitySystem, OptionalEntitySystem, PipeEntitySystem},
-        into_entity_system::{EntitySystemIntoSystem, IntoEntitySystem},
-        marked_entity_system::Data,
-        EntitySystem, ReadOnlyEntitySystem,
+        behaviour::{run_behaviours, Behaviour, Behaviours},
+        behaviour_tree::Status,
+        dyn_entity_system::{BoxedEntitySystem, DynEntitySystem},
+        entity_param::{EntityLocal, SelfCommands},
+        hooks::{HookKind, WorldEntitySystemHooksExt},
+        implementors::{
+            AdapterEntitySystem, FilteredEntitySystem, InputFromEntitySystem, IntoJoinEntitySystem,
+            JoinEntitySystem, OptionalEntitySystem, OrElseEntitySystem, PipeEntitySystem,
+            RunIfEntitySystem,
+        },
+        into_entity_system::{
+            EntitySystemIntoSystem, EntitySystemIntoSystemUntil, IntoEntitySystem,
+        },
+        into_system::EntityTargets,
+        marked_entity_system::{Data, InRef},
+        state_machine::{CurrentState, StateMachine},
+        system_registry::{
+            CommandsEntitySystemExt, EntityCommandsEntitySystemExt, EntitySystemId,
+            RegisteredEntitySystemError, WorldEntitySystemExt,
+        },
+        EntityMismatch, EntitySystem, ReadOnlyEntitySystem,
     };
     pub use bevy_entity_system_macros as macros;
 }
@@ -181,4 +226,1139 @@ mod tests {
         world.despawn(entity_with_10);
         assert_eq!(world.run_system(system).unwrap(), 26);
     }
+    #[test]
+    fn stateful_system_test() {
+        #[derive(Component)]
+        struct Count(u32);
+
+        fn count_runs(mut data: Data<&mut Count>, mut runs: Local<u32>) {
+            *runs += 1;
+            data.0 = *runs;
+        }
+
+        let mut world = World::new();
+        let first = world.spawn(Count(0)).id();
+
+        let system = world.register_system(count_runs.into_stateful_system());
+
+        world.run_system(system).unwrap();
+        world.run_system(system).unwrap();
+        let second = world.spawn(Count(0)).id();
+        world.run_system(system).unwrap();
+
+        assert_eq!(world.get::<Count>(first).unwrap().0, 3);
+        assert_eq!(world.get::<Count>(second).unwrap().0, 1);
+
+        world.entity_mut(first).remove::<Count>();
+        world.run_system(system).unwrap();
+        world.entity_mut(first).insert(Count(0));
+        world.run_system(system).unwrap();
+
+        assert_eq!(world.get::<Count>(first).unwrap().0, 1);
+        assert_eq!(world.get::<Count>(second).unwrap().0, 3);
+    }
+    #[test]
+    fn targeted_system_test() {
+        #[derive(Component)]
+        struct Count(u32);
+
+        struct Targets;
+
+        fn increment_count(mut data: Data<&mut Count>) {
+            data.0 += 1;
+        }
+
+        let mut world = World::new();
+        let targeted = world.spawn(Count(0)).id();
+        let other = world.spawn(Count(0)).id();
+        let mismatched = world.spawn_empty().id();
+
+        let system = world.register_system(increment_count.into_targeted_system::<Targets>());
+        assert!(world.run_system(system).unwrap().is_empty());
+
+        let mut targets = EntityTargets::<Targets>::default();
+        targets.insert(targeted);
+        targets.insert(mismatched);
+        world.insert_resource(targets);
+
+        assert_eq!(
+            world.run_system(system).unwrap(),
+            vec![EntityMismatch(mismatched)]
+        );
+        assert_eq!(world.get::<Count>(targeted).unwrap().0, 1);
+        assert_eq!(world.get::<Count>(other).unwrap().0, 0);
+
+        world.despawn(targeted);
+        assert_eq!(
+            world.run_system(system).unwrap(),
+            vec![EntityMismatch(mismatched)]
+        );
+
+        let targets = world.resource::<EntityTargets<Targets>>();
+        assert!(!targets.contains(targeted));
+        assert!(targets.contains(mismatched));
+    }
+    #[test]
+    fn registered_entity_system_test() {
+        #[derive(Component)]
+        struct Count(u32);
+
+        fn add_runs(input: In<u32>, mut data: Data<&mut Count>, mut runs: Local<u32>) -> u32 {
+            *runs += 1;
+            data.0 += *input * *runs;
+            data.0
+        }
+
+        let mut world = World::new();
+        let entity = world.spawn(Count(0)).id();
+        let empty = world.spawn_empty().id();
+
+        let system = world.register_entity_system(add_runs);
+
+        assert_eq!(world.run_registered_entity_system(system, entity, 1), Ok(1));
+        assert_eq!(world.run_registered_entity_system(system, entity, 1), Ok(3));
+        assert_eq!(
+            world.run_registered_entity_system(system, empty, 1),
+            Err(RegisteredEntitySystemError::Mismatch(EntityMismatch(empty)))
+        );
+
+        world
+            .commands()
+            .entity(entity)
+            .run_registered_entity_system(system, 2);
+        world.flush();
+        assert_eq!(world.get::<Count>(entity).unwrap().0, 9);
+
+        world
+            .commands()
+            .entity(entity)
+            .run_registered_entity_system(system, 2);
+        assert!(world.remove_entity_system(system));
+        assert!(!world.remove_entity_system(system));
+        world.flush();
+        assert_eq!(world.get::<Count>(entity).unwrap().0, 9);
+        assert_eq!(
+            world.run_registered_entity_system(system, entity, 1),
+            Err(RegisteredEntitySystemError::NotRegistered(system))
+        );
+    }
+    #[test]
+    fn par_system_test() {
+        #[derive(Component)]
+        struct Count(u32);
+
+        #[derive(Resource)]
+        struct Step(u32);
+
+        fn increment_count(mut data: Data<&mut Count>, step: Res<Step>) -> u32 {
+            data.0 += step.0;
+            data.0
+        }
+
+        bevy_tasks::ComputeTaskPool::get_or_init(bevy_tasks::TaskPool::default);
+
+        let mut world = World::new();
+        world.insert_resource(Step(2));
+        for i in 0..100 {
+            world.spawn(Count(i));
+        }
+
+        let system = world.register_system(increment_count.into_par_system_with_output(
+            |sum: &mut u32, count| *sum += count,
+            |sum, other| *sum += other,
+        ));
+
+        assert_eq!(world.run_system(system).unwrap(), 5150);
+        assert_eq!(world.run_system(system).unwrap(), 5350);
+    }
+    #[test]
+    fn self_commands_test() {
+        #[derive(Component)]
+        struct Health(i32);
+
+        #[derive(Component)]
+        struct Dead;
+
+        fn die(data: Data<&Health, Without<Dead>>, mut this: SelfCommands) {
+            if data.0 <= 0 {
+                this.insert(Dead);
+            }
+        }
+
+        fn despawn_dead(_data: Data<(), With<Dead>>, mut this: SelfCommands) {
+            this.despawn();
+        }
+
+        let mut world = World::new();
+        let alive = world.spawn(Health(10)).id();
+        let dead = world.spawn(Health(0)).id();
+
+        let die = world.register_system(die.into_system());
+        let despawn_dead = world.register_system(despawn_dead.into_system());
+
+        world.run_system(die).unwrap();
+        assert!(world.entity(dead).contains::<Dead>());
+        assert!(!world.entity(alive).contains::<Dead>());
+
+        world.run_system(despawn_dead).unwrap();
+        assert!(world.get_entity(dead).is_none());
+        assert!(world.get_entity(alive).is_some());
+    }
+    #[test]
+    fn entity_local_test() {
+        #[derive(Component)]
+        struct Count(u32);
+
+        fn count_runs(mut data: Data<&mut Count>, mut runs: EntityLocal<u32>) {
+            *runs += 1;
+            data.0 = *runs;
+        }
+
+        let mut world = World::new();
+        let first = world.spawn(Count(0)).id();
+
+        let system = world.register_system(count_runs.into_system());
+
+        world.run_system(system).unwrap();
+        world.run_system(system).unwrap();
+        let second = world.spawn(Count(0)).id();
+        world.run_system(system).unwrap();
+
+        assert_eq!(world.get::<Count>(first).unwrap().0, 3);
+        assert_eq!(world.get::<Count>(second).unwrap().0, 1);
+    }
+    #[test]
+    fn on_changed_system_test() {
+        #[derive(Component)]
+        struct Health(i32);
+
+        #[derive(Component, Default)]
+        struct Clamped(u32);
+
+        fn clamp_health(mut data: Data<(&mut Health, &mut Clamped)>) {
+            let (health, clamped) = &mut data.item;
+            let clamped_health = health.0.clamp(0, 100);
+            if health.0 != clamped_health {
+                health.0 = clamped_health;
+            }
+            clamped.0 += 1;
+        }
+
+        let mut world = World::new();
+        let changed = world.spawn((Health(200), Clamped::default())).id();
+        let other = world.spawn((Health(50), Clamped::default())).id();
+
+        let every_frame = world.register_system(clamp_health.into_system());
+        let on_changed = world.register_system(clamp_health.into_system_on_changed::<Health>());
+        let on_added = world.register_system(clamp_health.into_system_on_added::<Health>());
+
+        world.run_system(on_changed).unwrap();
+        world.run_system(on_added).unwrap();
+        assert_eq!(world.get::<Health>(changed).unwrap().0, 100);
+        assert_eq!(world.get::<Clamped>(other).unwrap().0, 2);
+
+        world.get_mut::<Health>(changed).unwrap().0 = -10;
+        world.run_system(on_changed).unwrap();
+        world.run_system(on_added).unwrap();
+        assert_eq!(world.get::<Health>(changed).unwrap().0, 0);
+        assert_eq!(world.get::<Clamped>(changed).unwrap().0, 3);
+        assert_eq!(world.get::<Clamped>(other).unwrap().0, 2);
+
+        world.run_system(every_frame).unwrap();
+        assert_eq!(world.get::<Clamped>(other).unwrap().0, 3);
+    }
+    #[test]
+    fn observer_test() {
+        #[derive(Event, Clone)]
+        struct Heal(i32);
+
+        #[derive(Component)]
+        struct Health(i32);
+
+        #[derive(Component)]
+        struct Undead;
+
+        fn heal(In(heal): In<Heal>, mut data: Data<&mut Health, Without<Undead>>) {
+            data.0 += heal.0;
+        }
+
+        let mut world = World::new();
+        let alive = world.spawn(Health(10)).id();
+        let undead = world.spawn((Health(10), Undead)).id();
+
+        world.observe(heal.into_observer::<()>());
+        world.flush();
+
+        world.trigger_targets(Heal(5), [alive, undead]);
+        world.trigger(Heal(5));
+
+        assert_eq!(world.get::<Health>(alive).unwrap().0, 15);
+        assert_eq!(world.get::<Health>(undead).unwrap().0, 10);
+    }
+    #[test]
+    fn hooks_test() {
+        #[derive(Component)]
+        struct Health(i32);
+
+        #[derive(Component, Default)]
+        struct Events {
+            added: u32,
+            inserted: u32,
+            removed: Vec<i32>,
+        }
+
+        let mut world = World::new();
+        let early = world.spawn((Events::default(), Health(5))).id();
+
+        world.register_entity_system_hook::<Health, _, _>(
+            HookKind::OnAdd,
+            |mut data: Data<&mut Events>| data.added += 1,
+        );
+        world.register_entity_system_hook::<Health, _, _>(
+            HookKind::OnInsert,
+            |mut data: Data<&mut Events>| data.inserted += 1,
+        );
+        let on_remove = world.register_entity_system_hook::<Health, _, _>(
+            HookKind::OnRemove,
+            |mut data: Data<(&mut Events, &Health)>| {
+                let (events, health) = &mut data.item;
+                events.removed.push(health.0);
+            },
+        );
+
+        let entity = world.spawn((Events::default(), Health(1))).id();
+        world.entity_mut(entity).insert(Health(2));
+        world.entity_mut(entity).remove::<Health>();
+        world.entity_mut(early).remove::<Health>();
+
+        let events = world.get::<Events>(entity).unwrap();
+        assert_eq!((events.added, events.inserted), (1, 2));
+        assert_eq!(events.removed, vec![2]);
+        assert_eq!(world.get::<Events>(early).unwrap().removed, vec![5]);
+
+        world.despawn(on_remove);
+        world.entity_mut(entity).insert(Health(3));
+        world.entity_mut(entity).remove::<Health>();
+
+        let events = world.get::<Events>(entity).unwrap();
+        assert_eq!((events.added, events.inserted), (2, 3));
+        assert_eq!(events.removed, vec![2]);
+    }
+    #[test]
+    fn event_system_test() {
+        #[derive(Event, Clone)]
+        struct Damage {
+            target: Entity,
+            amount: i32,
+        }
+
+        #[derive(Component)]
+        struct Health(i32);
+
+        fn take_damage(In(damage): In<Damage>, mut data: Data<&mut Health>) {
+            data.0 -= damage.amount;
+        }
+
+        let mut world = World::new();
+        world.init_resource::<Events<Damage>>();
+        let first = world.spawn(Health(10)).id();
+        let second = world.spawn(Health(10)).id();
+        let empty = world.spawn_empty().id();
+
+        let system = world.register_system(take_damage.into_event_system(|e: &Damage| e.target));
+
+        for (target, amount) in [(first, 3), (first, 2), (second, 4), (empty, 1)] {
+            world.send_event(Damage { target, amount });
+        }
+        world.run_system(system).unwrap();
+        world.run_system(system).unwrap();
+
+        assert_eq!(world.get::<Health>(first).unwrap().0, 5);
+        assert_eq!(world.get::<Health>(second).unwrap().0, 6);
+    }
+    #[test]
+    fn boxed_entity_system_test() {
+        #[derive(Component)]
+        struct Count(u32);
+
+        #[derive(Component)]
+        struct Marker;
+
+        fn increment(In(amount): In<u32>, mut data: Data<&mut Count>) -> u32 {
+            data.0 += amount;
+            data.0
+        }
+
+        fn mark(_: In<u32>, data: Data<(), Without<Marker>>, mut commands: Commands) -> u32 {
+            commands.entity(data.entity()).insert(Marker);
+            0
+        }
+
+        let mut world = World::new();
+        let entity = world.spawn(Count(0)).id();
+
+        let double = |In(count): In<u32>, _: Data<()>| count * 2;
+        let mut systems: Vec<BoxedEntitySystem<u32, u32>> = vec![
+            increment.boxed(),
+            mark.boxed(),
+            increment.pipe(double).boxed(),
+        ];
+
+        let outputs: Vec<_> = systems
+            .iter_mut()
+            .map(|system| system.run(2, entity, &mut world))
+            .collect();
+        assert_eq!(outputs, vec![Ok(2), Ok(0), Ok(8)]);
+        assert!(world.get::<Marker>(entity).is_some());
+
+        assert_eq!(systems[1].run(2, entity, &mut world), Err(EntityMismatch(entity)));
+    }
+    #[test]
+    fn behaviours_test() {
+        #[derive(Component)]
+        struct Count(u32);
+
+        fn increment(mut data: Data<&mut Count>) {
+            data.0 += 1;
+        }
+
+        fn spawn_counter(_: Data<()>, mut commands: Commands) {
+            commands.spawn(Count(100));
+        }
+
+        let mut world = World::new();
+        let single = world.spawn((Count(0), Behaviour::new(increment))).id();
+        let multiple = world
+            .spawn((
+                Count(0),
+                Behaviours::new().with(increment).with(increment),
+            ))
+            .id();
+        let spawner = world
+            .spawn(Behaviours::new().with(increment).with(spawn_counter))
+            .id();
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(run_behaviours);
+
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+
+        assert_eq!(world.get::<Count>(single).unwrap().0, 2);
+        assert_eq!(world.get::<Count>(multiple).unwrap().0, 4);
+        assert!(world.get::<Count>(spawner).is_none());
+        assert_eq!(world.query::<&Count>().iter(&world).count(), 4);
+
+        world.get_mut::<Behaviours>(multiple).unwrap().push(increment);
+        schedule.run(&mut world);
+        assert_eq!(world.get::<Count>(multiple).unwrap().0, 7);
+    }
+    #[test]
+    fn behaviour_tree_test() {
+        use crate::behaviour_tree::{invert, parallel, repeat, selector, sequence, until_success};
+
+        #[derive(Component)]
+        struct Count(u32);
+
+        #[derive(Component)]
+        struct Flag;
+
+        fn increment(mut data: Data<&mut Count>) -> Status {
+            data.0 += 1;
+            Status::Success
+        }
+
+        fn wait_for_three(data: Data<&Count>) -> Status {
+            if data.0 >= 3 {
+                Status::Success
+            } else {
+                Status::Running
+            }
+        }
+
+        fn has_flag(_: Data<(), With<Flag>>) -> Status {
+            Status::Success
+        }
+
+        let mut world = World::new();
+        let entity = world.spawn(Count(0)).id();
+        let flagged = world.spawn((Count(0), Flag)).id();
+
+        let tree = world.register_entity_system(sequence((
+            selector((has_flag, invert(has_flag), increment)),
+            repeat(2, increment),
+            parallel((until_success(wait_for_three), increment)),
+        )));
+
+        let run = |world: &mut World, entity| world.run_registered_entity_system(tree, entity, ());
+
+        assert_eq!(run(&mut world, entity), Ok(Status::Running));
+        assert_eq!(world.get::<Count>(entity).unwrap().0, 2);
+        assert_eq!(run(&mut world, entity), Ok(Status::Success));
+        assert_eq!(world.get::<Count>(entity).unwrap().0, 4);
+
+        assert_eq!(run(&mut world, flagged), Ok(Status::Running));
+        assert_eq!(world.get::<Count>(flagged).unwrap().0, 1);
+        assert_eq!(run(&mut world, flagged), Ok(Status::Running));
+        assert_eq!(world.get::<Count>(flagged).unwrap().0, 3);
+        assert_eq!(run(&mut world, flagged), Ok(Status::Success));
+        assert_eq!(world.get::<Count>(flagged).unwrap().0, 3);
+    }
+    #[test]
+    fn state_machine_test() {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        enum Light {
+            Green,
+            Red,
+        }
+
+        #[derive(Component, Default)]
+        struct Log(Vec<&'static str>);
+
+        fn green(mut data: Data<&mut Log>) -> Option<Light> {
+            data.0.push("green");
+            (data.0.len() > 2).then_some(Light::Red)
+        }
+
+        fn red(mut data: Data<&mut Log>) -> Option<Light> {
+            data.0.push("red");
+            Some(Light::Green)
+        }
+
+        let state_machine = StateMachine::new()
+            .state(Light::Green, green)
+            .state(Light::Red, red)
+            .on_enter(Light::Green, |mut data: Data<&mut Log>| data.0.push("enter green"))
+            .on_exit(Light::Green, |mut data: Data<&mut Log>| data.0.push("exit green"))
+            .on_enter(Light::Red, |mut data: Data<&mut Log>| data.0.push("enter red"));
+
+        let mut world = World::new();
+        let entity = world.spawn((CurrentState(Light::Green), Log::default())).id();
+        let system = world.register_system(state_machine.into_system());
+
+        for _ in 0..3 {
+            world.run_system(system).unwrap();
+        }
+
+        assert_eq!(world.get::<CurrentState<Light>>(entity).unwrap().0, Light::Green);
+        assert_eq!(
+            world.get::<Log>(entity).unwrap().0,
+            vec!["enter green", "green", "green", "exit green", "enter red", "red", "enter green"]
+        );
+    }
+    #[test]
+    fn choose_best_test() {
+        use crate::utility::choose_best;
+
+        #[derive(Component)]
+        struct Hunger(f32);
+
+        #[derive(Component)]
+        struct Food(u32);
+
+        fn hunger(data: Data<&Hunger>) -> f32 {
+            data.0
+        }
+
+        fn eat(mut data: Data<(&mut Hunger, &mut Food)>) -> &'static str {
+            let (hunger, food) = &mut data.item;
+            hunger.0 = 0.0;
+            food.0 -= 1;
+            "eat"
+        }
+
+        fn idle(_: Data<()>) -> &'static str {
+            "idle"
+        }
+
+        let mut world = World::new();
+        let with_food = world.spawn((Hunger(0.8), Food(1))).id();
+        let without_food = world.spawn(Hunger(0.8)).id();
+        let empty = world.spawn_empty().id();
+
+        let ai = world.register_entity_system(choose_best((
+            (hunger, eat),
+            (|_: Data<()>| 0.5, idle),
+        )));
+
+        assert_eq!(world.run_registered_entity_system(ai, with_food, ()), Ok(Some("eat")));
+        assert_eq!(world.get::<Food>(with_food).unwrap().0, 0);
+        assert_eq!(world.run_registered_entity_system(ai, with_food, ()), Ok(Some("idle")));
+        assert_eq!(world.run_registered_entity_system(ai, without_food, ()), Ok(Some("idle")));
+        assert_eq!(world.run_registered_entity_system(ai, empty, ()), Ok(Some("idle")));
+
+        let eat_only = world.register_entity_system(choose_best(((hunger, eat),)));
+        assert_eq!(
+            world.run_registered_entity_system(eat_only, empty, ()),
+            Err(RegisteredEntitySystemError::Mismatch(EntityMismatch(empty)))
+        );
+    }
+    #[test]
+    fn or_else_test() {
+        #[derive(Component)]
+        struct Health(i32);
+
+        #[derive(Component)]
+        struct Shield(i32);
+
+        fn hit_shield(In(damage): In<i32>, mut data: Data<&mut Shield>) -> &'static str {
+            data.0 -= damage;
+            "shield"
+        }
+
+        fn hit_health(In(damage): In<i32>, mut data: Data<&mut Health>) -> &'static str {
+            data.0 -= damage;
+            "health"
+        }
+
+        let mut world = World::new();
+        let shielded = world.spawn((Health(10), Shield(10))).id();
+        let unshielded = world.spawn(Health(10)).id();
+        let empty = world.spawn_empty().id();
+
+        let hit = world.register_entity_system(hit_shield.or_else(hit_health));
+
+        assert_eq!(world.run_registered_entity_system(hit, shielded, 3), Ok("shield"));
+        assert_eq!(world.run_registered_entity_system(hit, unshielded, 3), Ok("health"));
+        assert_eq!(
+            world.run_registered_entity_system(hit, empty, 3),
+            Err(RegisteredEntitySystemError::Mismatch(EntityMismatch(empty)))
+        );
+        assert_eq!(world.get::<Health>(shielded).unwrap().0, 10);
+        assert_eq!(world.get::<Shield>(shielded).unwrap().0, 7);
+        assert_eq!(world.get::<Health>(unshielded).unwrap().0, 7);
+
+        let system = world.register_system(
+            (|_: In<()>, mut data: Data<&mut Shield>| data.0 = 0)
+                .or_else(|_: In<()>, mut data: Data<&mut Health>| data.0 = 0)
+                .into_system(),
+        );
+        world.run_system(system).unwrap();
+        assert_eq!(world.get::<Shield>(shielded).unwrap().0, 0);
+        assert_eq!(world.get::<Health>(shielded).unwrap().0, 10);
+        assert_eq!(world.get::<Health>(unshielded).unwrap().0, 0);
+    }
+    #[test]
+    fn join_test() {
+        use crate::implementors::join;
+
+        #[derive(Component)]
+        struct Health(i32);
+
+        #[derive(Component)]
+        struct Mana(i32);
+
+        fn damage(In(amount): In<i32>, mut data: Data<&mut Health>) -> i32 {
+            data.0 -= amount;
+            data.0
+        }
+
+        fn drain(In(amount): In<i32>, mut data: Data<&mut Mana>) -> i32 {
+            data.0 -= amount;
+            data.0
+        }
+
+        let mut world = World::new();
+        let both = world.spawn((Health(10), Mana(5))).id();
+        let health_only = world.spawn(Health(10)).id();
+
+        assert_eq!(world.run_entity_system(both, join(damage, drain), 2), Ok((8, 3)));
+        assert_eq!(
+            world.run_entity_system(health_only, join(damage, drain), 2),
+            Err(EntityMismatch(health_only))
+        );
+
+        let outputs = world
+            .run_entity_system(both, (damage, drain, |_: In<i32>, _: Data<()>| "done").join(), 1)
+            .unwrap();
+        assert_eq!(outputs, (7, 2, "done"));
+
+        let system = world.register_system(
+            join(
+                |_: In<()>, mut data: Data<&mut Health>| data.0 = 0,
+                |_: In<()>, mut data: Data<&mut Mana>| data.0 = 0,
+            )
+            .map(|_| ())
+            .into_system(),
+        );
+        world.run_system(system).unwrap();
+        assert_eq!(world.get::<Health>(both).unwrap().0, 0);
+        assert_eq!(world.get::<Mana>(both).unwrap().0, 0);
+        assert_eq!(world.get::<Health>(health_only).unwrap().0, 10);
+    }
+    #[test]
+    fn run_if_entity_test() {
+        #[derive(Component)]
+        struct Health(i32);
+
+        #[derive(Component)]
+        struct Frozen;
+
+        fn is_alive(data: Data<&Health>) -> bool {
+            data.0 > 0
+        }
+
+        fn regenerate(mut data: Data<&mut Health, Without<Frozen>>) -> i32 {
+            data.0 += 1;
+            data.0
+        }
+
+        let mut world = World::new();
+        let alive = world.spawn(Health(5)).id();
+        let dead = world.spawn(Health(0)).id();
+        let frozen = world.spawn((Health(5), Frozen)).id();
+
+        let system = world.register_entity_system(regenerate.run_if_entity(is_alive));
+
+        assert_eq!(world.run_registered_entity_system(system, alive, ()), Ok(Some(6)));
+        assert_eq!(world.run_registered_entity_system(system, dead, ()), Ok(None));
+        assert_eq!(
+            world.run_registered_entity_system(system, frozen, ()),
+            Err(RegisteredEntitySystemError::Mismatch(EntityMismatch(frozen)))
+        );
+
+        let system = world.register_system(
+            (|mut data: Data<&mut Health>| data.0 = 100)
+                .run_if_entity(is_alive)
+                .map(|_| ())
+                .into_system(),
+        );
+        world.run_system(system).unwrap();
+        assert_eq!(world.get::<Health>(alive).unwrap().0, 100);
+        assert_eq!(world.get::<Health>(dead).unwrap().0, 0);
+        assert_eq!(world.get::<Health>(frozen).unwrap().0, 100);
+    }
+    #[test]
+    fn map_input_test() {
+        #[derive(Component)]
+        struct Position(i32);
+
+        #[derive(Component)]
+        struct Speed(u32);
+
+        fn speed(data: Data<&Speed>) -> u32 {
+            data.0
+        }
+
+        fn move_by(In(offset): In<i32>, mut data: Data<&mut Position>) -> i32 {
+            data.0 += offset;
+            data.0
+        }
+
+        let mut world = World::new();
+        let entity = world.spawn((Position(0), Speed(3))).id();
+
+        let system = speed.pipe(move_by.map_in(|speed: u32| speed as i32 * 2));
+        assert_eq!(world.run_entity_system(entity, system, ()), Ok(6));
+
+        let system = world.register_entity_system(move_by.with_input(-1));
+        assert_eq!(world.run_registered_entity_system(system, entity, ()), Ok(5));
+        assert_eq!(world.run_registered_entity_system(system, entity, ()), Ok(4));
+
+        let system = world.register_system(move_by.with_input(10).map(|_| ()).into_system());
+        world.run_system(system).unwrap();
+        assert_eq!(world.get::<Position>(entity).unwrap().0, 14);
+    }
+    #[test]
+    fn input_from_test() {
+        #[derive(Component, Clone)]
+        struct Intent(i32);
+
+        #[derive(Component)]
+        struct Position(i32);
+
+        fn read_intent(In(intent): In<Intent>, _: Data<()>) -> i32 {
+            intent.0 * 2
+        }
+
+        fn apply_move(In(offset): In<i32>, mut data: Data<&mut Position>) {
+            data.0 += offset;
+        }
+
+        let mut world = World::new();
+        let moving = world.spawn((Position(0), Intent(3))).id();
+        let still = world.spawn(Position(0)).id();
+
+        let system = world.register_system(
+            read_intent
+                .input_from::<Intent>()
+                .pipe(apply_move)
+                .into_system(),
+        );
+        world.run_system(system).unwrap();
+        world.run_system(system).unwrap();
+
+        assert_eq!(world.get::<Position>(moving).unwrap().0, 12);
+        assert_eq!(world.get::<Position>(still).unwrap().0, 0);
+        assert_eq!(
+            world.run_entity_system(still, read_intent.input_from::<Intent>(), ()),
+            Err(EntityMismatch(still))
+        );
+    }
+    #[test]
+    fn in_ref_test() {
+        struct Hits(Vec<Entity>);
+
+        #[derive(Component)]
+        struct Hit(Entity);
+
+        #[derive(Component)]
+        struct Health(i32);
+
+        fn find_hits(query: Query<&Hit>) -> Hits {
+            Hits(query.iter().map(|hit| hit.0).collect())
+        }
+
+        fn take_hits(hits: InRef<Hits>, mut data: Data<&mut Health>) {
+            let count = hits.0.iter().filter(|hit| **hit == data.entity()).count();
+            data.0 -= count as i32;
+        }
+
+        let mut world = World::new();
+        let first = world.spawn(Health(10)).id();
+        let second = world.spawn(Health(10)).id();
+        world.spawn_batch([Hit(first), Hit(second), Hit(first)]);
+
+        let system = world.register_system(IntoSystem::pipe(find_hits, take_hits.into_system_ref()));
+        world.run_system(system).unwrap();
+
+        assert_eq!(world.get::<Health>(first).unwrap().0, 8);
+        assert_eq!(world.get::<Health>(second).unwrap().0, 9);
+
+        let system = world.register_system(take_hits.into_system_ref());
+        world.run_system_with_input(system, Hits(vec![second])).unwrap();
+
+        assert_eq!(world.get::<Health>(first).unwrap().0, 8);
+        assert_eq!(world.get::<Health>(second).unwrap().0, 8);
+    }
+    #[test]
+    fn system_with_inputs_test() {
+        use bevy_ecs::entity::EntityHashMap;
+
+        #[derive(Component)]
+        struct Target(Entity);
+
+        #[derive(Component)]
+        struct Health(i32);
+
+        fn take_damage(In(damage): In<i32>, mut data: Data<&mut Health>) {
+            data.0 -= damage;
+        }
+
+        fn attack(attackers: Query<&Target>) -> EntityHashMap<i32> {
+            let mut damage = EntityHashMap::default();
+            for target in attackers.iter() {
+                *damage.entry(target.0).or_default() += 2;
+            }
+            damage
+        }
+
+        let mut world = World::new();
+        let victim = world.spawn(Health(10)).id();
+        let bystander = world.spawn(Health(10)).id();
+        let empty = world.spawn_empty().id();
+        world.spawn_batch([Target(victim), Target(victim), Target(empty)]);
+
+        let system = world.register_system(IntoSystem::pipe(attack, take_damage.into_system_with_inputs()));
+
+        assert_eq!(world.run_system(system).unwrap(), vec![EntityMismatch(empty)]);
+        assert_eq!(world.get::<Health>(victim).unwrap().0, 6);
+        assert_eq!(world.get::<Health>(bystander).unwrap().0, 10);
+    }
+    #[test]
+    fn data_entity_test() {
+        #[derive(Component, Default)]
+        struct Seen(Vec<Entity>);
+
+        fn see(mut data: Data<&mut Seen>) {
+            let entity = data.entity();
+            data.0.push(entity);
+        }
+
+        fn entity_of(data: Data<&Seen>) -> Entity {
+            data.entity()
+        }
+
+        fn see_piped(In(piped): In<Entity>, mut data: Data<&mut Seen>) {
+            let entity = data.entity();
+            data.0.extend([piped, entity]);
+        }
+
+        let mut world = World::new();
+        let a = world.spawn(Seen::default()).id();
+        let b = world.spawn(Seen::default()).id();
+        let empty = world.spawn_empty().id();
+
+        let system = world.register_system(see.into_system());
+        world.run_system(system).unwrap();
+
+        let system = world.register_system(entity_of.pipe(see_piped).into_system());
+        world.run_system(system).unwrap();
+
+        assert_eq!(world.run_entity_system(a, see.optional(), ()), Ok(Ok(())));
+        assert_eq!(world.run_entity_system(empty, see.optional(), ()), Ok(Err(())));
+
+        assert_eq!(world.get::<Seen>(a).unwrap().0, vec![a; 4]);
+        assert_eq!(world.get::<Seen>(b).unwrap().0, vec![b; 3]);
+    }
+    #[test]
+    fn collect_entities_test() {
+        #[derive(Component)]
+        struct Damage(i32);
+
+        fn compute_damage(In(multiplier): In<i32>, data: Data<&Damage>) -> i32 {
+            data.0 * multiplier
+        }
+
+        let mut world = World::new();
+        let a = world.spawn(Damage(1)).id();
+        let b = world.spawn(Damage(5)).id();
+        let empty = world.spawn_empty().id();
+
+        let system = world.register_system(compute_damage.into_system_collect_entities());
+        let map = world.run_system_with_input(system, 2).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&a), Some(&2));
+        assert_eq!(map.get(&b), Some(&10));
+        assert!(!map.contains_key(&empty));
+
+        let system = world.register_system(compute_damage.into_system_collect_entities_vec());
+        let mut pairs = world.run_system_with_input(system, 3).unwrap();
+        pairs.sort_by_key(|(_, damage)| *damage);
+        assert_eq!(pairs, vec![(a, 3), (b, 15)]);
+    }
+    #[test]
+    fn behaviour_tree_reset_test() {
+        use crate::behaviour_tree::{parallel, repeat, sequence};
+
+        #[derive(Component, Default)]
+        struct Log(Vec<&'static str>);
+
+        fn a(mut data: Data<&mut Log>) -> Status {
+            data.0.push("a");
+            Status::Success
+        }
+
+        fn b(mut data: Data<&mut Log>) -> Status {
+            data.0.push("b");
+            Status::Running
+        }
+
+        fn fail_once(mut data: Data<&mut Log>) -> Status {
+            let failed = data.0.contains(&"f");
+            data.0.push("f");
+            if failed {
+                Status::Running
+            } else {
+                Status::Failure
+            }
+        }
+
+        let mut world = World::new();
+        let entity = world.spawn(Log::default()).id();
+
+        let tree = world.register_entity_system(parallel((sequence((a, b)), fail_once)));
+        assert_eq!(world.run_registered_entity_system(tree, entity, ()), Ok(Status::Failure));
+        assert_eq!(world.run_registered_entity_system(tree, entity, ()), Ok(Status::Running));
+        assert_eq!(world.get::<Log>(entity).unwrap().0, vec!["a", "b", "f", "a", "b", "f"]);
+
+        world.entity_mut(entity).insert(Log::default());
+        let tree = world.register_entity_system(parallel((repeat(2, a), fail_once)));
+        assert_eq!(world.run_registered_entity_system(tree, entity, ()), Ok(Status::Failure));
+        assert_eq!(world.run_registered_entity_system(tree, entity, ()), Ok(Status::Running));
+        assert_eq!(world.run_registered_entity_system(tree, entity, ()), Ok(Status::Running));
+        assert_eq!(world.get::<Log>(entity).unwrap().0, vec!["a", "f", "a", "f", "a", "f"]);
+    }
+    #[test]
+    #[should_panic(expected = "B0001")]
+    fn stateful_system_conflict_test() {
+        #[derive(Component)]
+        struct Count(u32);
+
+        fn conflicting(mut data: Data<&mut Count>, query: Query<&Count>) {
+            data.0 = query.iter().count() as u32;
+        }
+
+        let mut world = World::new();
+        world.spawn(Count(0));
+
+        let system = world.register_system(conflicting.into_stateful_system());
+        world.run_system(system).unwrap();
+    }
+    #[test]
+    fn entity_local_per_system_test() {
+        #[derive(Component, Default)]
+        struct Counts {
+            a: u32,
+            b: u32,
+        }
+
+        fn add_one(mut data: Data<&mut Counts>, mut local: EntityLocal<u32>) {
+            *local += 1;
+            data.a = *local;
+        }
+
+        fn add_ten(mut data: Data<&mut Counts>, mut local: EntityLocal<u32>) {
+            *local += 10;
+            data.b = *local;
+        }
+
+        let mut world = World::new();
+        let entity = world.spawn(Counts::default()).id();
+
+        let a = world.register_system(add_one.into_system());
+        let b = world.register_system(add_ten.into_system());
+
+        for _ in 0..3 {
+            world.run_system(a).unwrap();
+            world.run_system(b).unwrap();
+        }
+
+        let counts = world.get::<Counts>(entity).unwrap();
+        assert_eq!((counts.a, counts.b), (3, 30));
+    }
+    #[test]
+    fn boxed_reset_test() {
+        use crate::behaviour::{run_behaviours, Behaviour};
+        use crate::behaviour_tree::sequence;
+
+        #[derive(Component, Default)]
+        struct Log(Vec<&'static str>);
+
+        fn a(mut data: Data<&mut Log>) -> Status {
+            data.0.push("a");
+            Status::Success
+        }
+
+        fn b(mut data: Data<&mut Log>) -> Status {
+            data.0.push("b");
+            Status::Running
+        }
+
+        let mut world = World::new();
+        let entity = world.spawn(Log::default()).id();
+
+        let tree = world.register_entity_system(sequence((a, b)));
+        assert_eq!(world.run_registered_entity_system(tree, entity, ()), Ok(Status::Running));
+        assert_eq!(world.reset_registered_entity_system(tree, entity), Ok(()));
+        assert_eq!(world.run_registered_entity_system(tree, entity, ()), Ok(Status::Running));
+        assert_eq!(world.run_registered_entity_system(tree, entity, ()), Ok(Status::Running));
+        assert_eq!(world.get::<Log>(entity).unwrap().0, vec!["a", "b", "a", "b", "b"]);
+
+        world.remove_entity_system(tree);
+        assert_eq!(
+            world.reset_registered_entity_system(tree, entity),
+            Err(RegisteredEntitySystemError::NotRegistered(tree))
+        );
+
+        world.entity_mut(entity).insert((
+            Log::default(),
+            Behaviour::new(sequence((a, b)).map(|_| ())),
+        ));
+
+        let mut schedule = Schedule::default();
+        schedule.add_systems(run_behaviours);
+        schedule.run(&mut world);
+        world.get_mut::<Behaviour>(entity).unwrap().reset(entity);
+        schedule.run(&mut world);
+        schedule.run(&mut world);
+        assert_eq!(world.get::<Log>(entity).unwrap().0, vec!["a", "b", "a", "b", "b"]);
+    }
+    #[test]
+    fn state_machine_reset_test() {
+        use crate::behaviour_tree::sequence;
+
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        enum Light {
+            Green,
+            Red,
+        }
+
+        #[derive(Component, Default)]
+        struct Log(Vec<&'static str>);
+
+        fn a(mut data: Data<&mut Log>) -> Status {
+            data.0.push("a");
+            Status::Success
+        }
+
+        fn b(mut data: Data<&mut Log>) -> Status {
+            data.0.push("b");
+            Status::Running
+        }
+
+        fn switch(_: In<Status>, data: Data<&Log>) -> Option<Light> {
+            (data.0.len() == 3).then_some(Light::Red)
+        }
+
+        let state_machine = StateMachine::new()
+            .state(Light::Green, sequence((a, b)).pipe(switch))
+            .state(Light::Red, |_: Data<()>| Some(Light::Green));
+
+        let mut world = World::new();
+        let entity = world.spawn((CurrentState(Light::Green), Log::default())).id();
+        let system = world.register_system(state_machine.into_system());
+
+        for _ in 0..4 {
+            world.run_system(system).unwrap();
+        }
+
+        assert_eq!(world.get::<Log>(entity).unwrap().0, vec!["a", "b", "b", "a", "b"]);
+    }
+    #[test]
+    fn choose_best_reset_test() {
+        use crate::behaviour_tree::sequence;
+        use crate::utility::choose_best;
+
+        #[derive(Component, Default)]
+        struct Log(Vec<&'static str>);
+
+        #[derive(Component)]
+        struct Walk(f32);
+
+        fn a(mut data: Data<&mut Log>) -> Status {
+            data.0.push("a");
+            Status::Success
+        }
+
+        fn b(mut data: Data<&mut Log>) -> Status {
+            data.0.push("b");
+            Status::Running
+        }
+
+        fn walk(data: Data<&Walk>) -> f32 {
+            data.0
+        }
+
+        fn rest(mut data: Data<&mut Log>) -> Status {
+            data.0.push("rest");
+            Status::Success
+        }
+
+        let mut world = World::new();
+        let entity = world.spawn((Log::default(), Walk(1.0))).id();
+        let empty = world.spawn_empty().id();
+
+        let ai = world.register_entity_system(choose_best((
+            (walk, sequence((a, b))),
+            (|_: Data<()>| 0.5, rest),
+        )));
+
+        for walk in [1.0, 0.0, 1.0] {
+            world.get_mut::<Walk>(entity).unwrap().0 = walk;
+            world.run_registered_entity_system(ai, entity, ()).unwrap();
+        }
+
+        assert_eq!(world.get::<Log>(entity).unwrap().0, vec!["a", "b", "rest", "a", "b"]);
+
+        let tree = world.register_entity_system(sequence((a, b)));
+        assert_eq!(
+            world.run_registered_entity_system(tree, empty, ()),
+            Err(RegisteredEntitySystemError::Mismatch(EntityMismatch(empty)))
+        );
+
+        let system = world.register_system(sequence((a, b)).into_system_collect_entities_vec());
+        let outputs = world.run_system(system).unwrap();
+        assert_eq!(outputs, vec![(entity, Status::Running)]);
+    }
 }
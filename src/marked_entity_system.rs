@@ -1,8 +1,12 @@
 //! Contains functionality to run [`EntitySystem`]s that should be marked in order to implement this trait
 //! This is necessary to avoid conflicting implementations when implementing trait for rust functions
 
-use crate::EntitySystem;
+use crate::{
+    entity_param::{EntitySystemParam, EntitySystemParamItem},
+    EntitySystem,
+};
 use bevy_ecs::{
+    entity::Entity,
     query::{QueryData, QueryFilter, QueryItem},
     system::{In, SystemParam, SystemParamFunction, SystemParamItem},
 };
@@ -11,6 +15,7 @@ use bevy_utils::all_tuples;
 use std::{
     marker::PhantomData,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
 /// Runs [`MarkedEntitySystem`]
@@ -28,6 +33,15 @@ impl<Marker: 'static, T: MarkedEntitySystem<Marker>> MarkedEntitySystemRunner<Ma
     }
 }
 
+impl<Marker: 'static, T: MarkedEntitySystem<Marker> + Clone> Clone
+    for MarkedEntitySystemRunner<Marker, T>
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        MarkedEntitySystemRunner(self.0.clone(), PhantomData)
+    }
+}
+
 impl<Marker: 'static, T: MarkedEntitySystem<Marker>> EntitySystem
     for MarkedEntitySystemRunner<Marker, T>
 {
@@ -41,10 +55,11 @@ impl<Marker: 'static, T: MarkedEntitySystem<Marker>> EntitySystem
     fn run(
         &mut self,
         input: Self::In,
+        entity: Entity,
         data_value: QueryItem<Self::Data>,
         param_value: SystemParamItem<Self::Param>,
     ) -> Self::Out {
-        self.0.run(input, data_value, param_value)
+        self.0.run(input, entity, data_value, param_value)
     }
 }
 
@@ -68,10 +83,11 @@ pub trait MarkedEntitySystem<Marker>: Send + Sync + 'static {
     /// Output of the function
     type Out;
 
-    /// Executes this system once.
+    /// Executes this system once for the `entity`.
     fn run(
         &mut self,
         input: Self::In,
+        entity: Entity,
         data_value: QueryItem<Self::Data>,
         param_value: SystemParamItem<Self::Param>,
     ) -> Self::Out;
@@ -91,6 +107,7 @@ impl<T: SystemParamFunction<M>, M> MarkedEntitySystem<(IsSystemParamFunction, M)
     fn run(
             &mut self,
             input: Self::In,
+            _entity: Entity,
             _data_value: QueryItem<Self::Data>,
             param_value: SystemParamItem<Self::Param>,
         ) -> Self::Out {
@@ -102,6 +119,7 @@ impl<T: SystemParamFunction<M>, M> MarkedEntitySystem<(IsSystemParamFunction, M)
 pub struct Data<'w, D: QueryData, F: QueryFilter = ()> {
     /// Item of the `QueryData`. What you get by calling `Query::get` or similar methods
     pub item: D::Item<'w>,
+    entity: Entity,
     marker: PhantomData<F>,
 }
 
@@ -122,14 +140,35 @@ impl<'w, D: QueryData, F: QueryFilter> DerefMut for Data<'w, D, F> {
 }
 
 impl<'w, D: QueryData, F: QueryFilter> Data<'w, D, F> {
-    /// New instance of `Data` with provided item
-    pub fn new(item: D::Item<'w>) -> Self {
+    /// New instance of `Data` with provided item of the `entity`
+    pub fn new(entity: Entity, item: D::Item<'w>) -> Self {
         Data {
             item,
+            entity,
             marker: PhantomData,
         }
     }
 
+    /// Entity that system is being run on
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Health(i32);
+    ///
+    /// fn despawn_dead(data: Data<&Health>, mut commands: Commands) {
+    ///     if data.0 <= 0 {
+    ///         commands.entity(data.entity()).despawn();
+    ///     }
+    /// }
+    /// # bevy_ecs::system::assert_is_system(despawn_dead.into_system());
+    /// ```
+    #[inline]
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+
     /// Converts `Data` into inner item. Basically the same as `let _ = data.item`
     #[inline]
     pub fn into_inner(self) -> D::Item<'w> {
@@ -137,6 +176,46 @@ impl<'w, D: QueryData, F: QueryFilter> Data<'w, D, F> {
     }
 }
 
+/// Input of the entity system that is shared between all the entities system is run on,
+/// instead of being cloned for each of them. Can be used as the first parameter of the function
+/// in place of [`In`], derefs to `&T`.
+///
+/// Use [`into_system_ref`](crate::into_entity_system::IntoEntitySystem::into_system_ref)
+/// to turn such entity system into [`System`](bevy_ecs::system::System) that takes `T` itself,
+/// so `T` doesn't need to be [`Clone`].
+pub struct InRef<T>(Arc<T>);
+
+impl<T> InRef<T> {
+    /// Creates new shared input
+    #[inline]
+    pub fn new(value: T) -> Self {
+        InRef(Arc::new(value))
+    }
+}
+
+impl<T> Clone for InRef<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        InRef(self.0.clone())
+    }
+}
+
+impl<T> Deref for InRef<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> From<T> for InRef<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        InRef::new(value)
+    }
+}
+
 macro_rules! impl_entity_system_function {
     ($($param: ident),*) => {
         #[allow(non_snake_case)]
@@ -145,23 +224,23 @@ macro_rules! impl_entity_system_function {
             QFilter: QueryFilter,
             Out,
             Func: Send + Sync + 'static,
-            $($param: SystemParam + 'static),*
+            $($param: EntitySystemParam + 'static),*
         > MarkedEntitySystem<fn(Data<QData, QFilter>, $($param,)*) -> Out> for Func
         where
         for <'a> &'a mut Func:
                 FnMut(Data<QData, QFilter>, $($param),*) -> Out +
-                FnMut(Data<QData, QFilter>, $(SystemParamItem<$param>),*) -> Out,
+                FnMut(Data<QData, QFilter>, $(EntitySystemParamItem<$param>),*) -> Out,
                 QData: 'static, QFilter: 'static, Out: 'static
         {
             type Data = QData;
             type Filter = QFilter;
-            type Param = ($($param,)*);
+            type Param = ($($param::Param,)*);
 
             type In = ();
             type Out = Out;
 
             #[inline]
-            fn run(&mut self, _input: (), data_value: QueryItem<QData>, param_value: SystemParamItem< ($($param,)*)>) -> Out {
+            fn run(&mut self, _input: (), entity: Entity, data_value: QueryItem<QData>, param_value: SystemParamItem<Self::Param>) -> Out {
                 // Yes, this is strange, but `rustc` fails to compile this impl
                 // without using this function. It fails to recognize that `func`
                 // is a function, potentially because of the multiple impls of `FnMut`
@@ -175,8 +254,8 @@ macro_rules! impl_entity_system_function {
                 }
 
                 let ($($param,)*) = param_value;
-                let data = Data::new(data_value);
-                call_inner(self, data, $($param),*)
+                let data = Data::new(entity, data_value);
+                call_inner(self, data, $(<$param as EntitySystemParam>::get_param(entity, $param)),*)
             }
         }
 
@@ -189,23 +268,23 @@ macro_rules! impl_entity_system_function {
             Input,
             Out,
             Func: Send + Sync + 'static,
-            $($param: SystemParam + 'static),*
+            $($param: EntitySystemParam + 'static),*
         > MarkedEntitySystem<fn(In<Input>, Data<QData, QFilter>, $($param,)*) -> Out> for Func
         where
         for <'a> &'a mut Func:
                 FnMut(In<Input>, Data<QData, QFilter>, $($param),*) -> Out +
-                FnMut(In<Input>, Data<QData, QFilter>, $(SystemParamItem<$param>),*) -> Out,
+                FnMut(In<Input>, Data<QData, QFilter>, $(EntitySystemParamItem<$param>),*) -> Out,
                 QData: 'static, QFilter: 'static, Out: 'static
         {
             type Data = QData;
             type Filter = QFilter;
-            type Param = ($($param,)*);
+            type Param = ($($param::Param,)*);
 
             type In = Input;
             type Out = Out;
 
             #[inline]
-            fn run(&mut self, input: Input, data_value: QueryItem<QData>, param_value: SystemParamItem< ($($param,)*)>) -> Out {
+            fn run(&mut self, input: Input, entity: Entity, data_value: QueryItem<QData>, param_value: SystemParamItem<Self::Param>) -> Out {
                 // Yes, this is strange, but `rustc` fails to compile this impl
                 // without using this function. It fails to recognize that `func`
                 // is a function, potentially because of the multiple impls of `FnMut`
@@ -220,8 +299,53 @@ macro_rules! impl_entity_system_function {
                 }
 
                 let ($($param,)*) = param_value;
-                let data = Data::new(data_value);
-                call_inner(self, In(input), data, $($param),*)
+                let data = Data::new(entity, data_value);
+                call_inner(self, In(input), data, $(<$param as EntitySystemParam>::get_param(entity, $param)),*)
+            }
+        }
+
+
+
+        #[allow(non_snake_case)]
+        impl<
+            QData: QueryData,
+            QFilter: QueryFilter,
+            Input,
+            Out,
+            Func: Send + Sync + 'static,
+            $($param: EntitySystemParam + 'static),*
+        > MarkedEntitySystem<fn(InRef<Input>, Data<QData, QFilter>, $($param,)*) -> Out> for Func
+        where
+        for <'a> &'a mut Func:
+                FnMut(InRef<Input>, Data<QData, QFilter>, $($param),*) -> Out +
+                FnMut(InRef<Input>, Data<QData, QFilter>, $(EntitySystemParamItem<$param>),*) -> Out,
+                QData: 'static, QFilter: 'static, Out: 'static
+        {
+            type Data = QData;
+            type Filter = QFilter;
+            type Param = ($($param::Param,)*);
+
+            type In = InRef<Input>;
+            type Out = Out;
+
+            #[inline]
+            fn run(&mut self, input: InRef<Input>, entity: Entity, data_value: QueryItem<QData>, param_value: SystemParamItem<Self::Param>) -> Out {
+                // Yes, this is strange, but `rustc` fails to compile this impl
+                // without using this function. It fails to recognize that `func`
+                // is a function, potentially because of the multiple impls of `FnMut`
+                #[allow(clippy::too_many_arguments)]
+                fn call_inner<QData: QueryData, QFilter: QueryFilter, Input, Out, $($param,)*>(
+                    mut f: impl FnMut(InRef<Input>, Data<QData, QFilter>, $($param,)*)->Out,
+                    input: InRef<Input>,
+                    data: Data<QData, QFilter>,
+                    $($param: $param,)*
+                )->Out{
+                    f(input, data, $($param,)*)
+                }
+
+                let ($($param,)*) = param_value;
+                let data = Data::new(entity, data_value);
+                call_inner(self, input, data, $(<$param as EntitySystemParam>::get_param(entity, $param)),*)
             }
         }
 
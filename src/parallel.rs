@@ -0,0 +1,128 @@
+//! Access to read-only [`SystemParam`]s from multiple threads at once
+
+use bevy_ecs::{
+    archetype::Archetype,
+    component::Tick,
+    system::{ReadOnlySystemParam, SystemMeta, SystemParam, SystemParamItem},
+    world::{unsafe_world_cell::UnsafeWorldCell, DeferredWorld, World},
+};
+use std::{
+    num::NonZeroUsize,
+    sync::{Mutex, MutexGuard, PoisonError},
+    thread,
+};
+
+/// [`SystemParam`] that stores separate state of `P` for every thread
+/// that can run simultaneously, so `P` can be fetched by every one of them.
+pub(crate) struct ParallelParam<'w, 's, P: ReadOnlySystemParam> {
+    slots: &'s [Mutex<P::State>],
+    system_meta: SystemMeta,
+    world: UnsafeWorldCell<'w>,
+    change_tick: Tick,
+}
+
+impl<'w, 's, P: ReadOnlySystemParam> ParallelParam<'w, 's, P> {
+    /// Locks state of `P` that isn't used by other threads.
+    /// If all of the states are used, waits for the first one to be freed.
+    pub(crate) fn fetcher(&self) -> ParallelParamFetcher<'_, 'w, P> {
+        let state = self
+            .slots
+            .iter()
+            .find_map(|slot| slot.try_lock().ok())
+            .unwrap_or_else(|| self.slots[0].lock().unwrap_or_else(PoisonError::into_inner));
+
+        ParallelParamFetcher {
+            state,
+            system_meta: &self.system_meta,
+            world: self.world,
+            change_tick: self.change_tick,
+        }
+    }
+}
+
+/// Locked state of the [`ParallelParam`]
+pub(crate) struct ParallelParamFetcher<'a, 'w, P: SystemParam> {
+    state: MutexGuard<'a, P::State>,
+    system_meta: &'a SystemMeta,
+    world: UnsafeWorldCell<'w>,
+    change_tick: Tick,
+}
+
+impl<'w, P: ReadOnlySystemParam> ParallelParamFetcher<'_, 'w, P> {
+    /// Fetches `P` using the locked state
+    #[inline]
+    pub(crate) fn get(&mut self) -> SystemParamItem<'w, '_, P> {
+        // SAFETY:
+        // Every state was initialized with the same `SystemMeta` and world,
+        // so access of `P` was registered and checked for conflicts with other params of the system.
+        // `P` is read-only, so it doesn't conflict with the `P`s fetched by other threads,
+        // and the state is locked, so no other thread uses it at the same time.
+        unsafe {
+            P::get_param(
+                &mut self.state,
+                self.system_meta,
+                self.world,
+                self.change_tick,
+            )
+        }
+    }
+}
+
+/// SAFETY:
+/// Every state of `P` registers the same access using `init_state` of `P`.
+/// `P` is read-only, so the same access can be fetched from multiple threads.
+unsafe impl<P: ReadOnlySystemParam> SystemParam for ParallelParam<'_, '_, P> {
+    type State = Vec<Mutex<P::State>>;
+    type Item<'w, 's> = ParallelParam<'w, 's, P>;
+
+    fn init_state(world: &mut World, system_meta: &mut SystemMeta) -> Self::State {
+        let count = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+
+        (0..count)
+            .map(|_| Mutex::new(P::init_state(world, system_meta)))
+            .collect()
+    }
+
+    unsafe fn new_archetype(
+        state: &mut Self::State,
+        archetype: &Archetype,
+        system_meta: &mut SystemMeta,
+    ) {
+        for slot in state {
+            let slot = slot.get_mut().unwrap_or_else(PoisonError::into_inner);
+            // SAFETY: Caller guarantees that `archetype` is from the world used to initialize `state`
+            unsafe { P::new_archetype(slot, archetype, system_meta) };
+        }
+    }
+
+    fn apply(state: &mut Self::State, system_meta: &SystemMeta, world: &mut World) {
+        for slot in state {
+            let slot = slot.get_mut().unwrap_or_else(PoisonError::into_inner);
+            P::apply(slot, system_meta, world);
+        }
+    }
+
+    fn queue(state: &mut Self::State, system_meta: &SystemMeta, mut world: DeferredWorld) {
+        for slot in state {
+            let slot = slot.get_mut().unwrap_or_else(PoisonError::into_inner);
+            P::queue(slot, system_meta, world.reborrow());
+        }
+    }
+
+    unsafe fn get_param<'w, 's>(
+        state: &'s mut Self::State,
+        system_meta: &SystemMeta,
+        world: UnsafeWorldCell<'w>,
+        change_tick: Tick,
+    ) -> Self::Item<'w, 's> {
+        ParallelParam {
+            slots: state,
+            system_meta: system_meta.clone(),
+            world,
+            change_tick,
+        }
+    }
+}
+
+/// SAFETY: Only read-only `P` is fetched
+unsafe impl<P: ReadOnlySystemParam> ReadOnlySystemParam for ParallelParam<'_, '_, P> {}
@@ -0,0 +1,235 @@
+//! Finite state machines, which states are [`EntitySystem`](crate::EntitySystem)s.
+//! Current state of every entity is stored in the [`CurrentState`] component
+
+use crate::{dyn_entity_system::BoxedEntitySystem, into_entity_system::IntoEntitySystem};
+use bevy_ecs::{
+    change_detection::DetectChanges,
+    component::Component,
+    entity::Entity,
+    query::QueryState,
+    system::{IntoSystem, System},
+    world::{Ref, World},
+};
+use bevy_utils::HashMap;
+use std::hash::Hash;
+
+/// Component that stores current state of the entity in the [`StateMachine`] with states `S`.
+///
+/// Changing it manually doesn't run [`on_exit`](StateMachine::on_exit) and
+/// [`on_enter`](StateMachine::on_enter) systems.
+#[derive(Component, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CurrentState<S: Clone + Eq + Hash + Send + Sync + 'static>(pub S);
+
+/// Entity systems of the single state
+struct StateSystems<S> {
+    update: Option<BoxedEntitySystem<(), Option<S>>>,
+    on_enter: Vec<BoxedEntitySystem>,
+    on_exit: Vec<BoxedEntitySystem>,
+}
+
+impl<S> Default for StateSystems<S> {
+    fn default() -> Self {
+        StateSystems {
+            update: None,
+            on_enter: Vec::new(),
+            on_exit: Vec::new(),
+        }
+    }
+}
+
+/// Finite state machine, where every state is an entity system that returns the state to transition to.
+/// Runs for every entity with [`CurrentState<S>`] component.
+///
+/// When entity system of the state returns `Some` state, different from the current one,
+/// [`on_exit`](StateMachine::on_exit) systems of the current state are run,
+/// [`CurrentState`] is changed and [`on_enter`](StateMachine::on_enter) systems of the new state are run.
+/// `on_enter` systems are also run for the entities that just got `CurrentState` component.
+/// After `on_exit` systems are run, every entity system of the exited state is reset
+/// for the entity, see [`EntitySystem::reset`](crate::EntitySystem::reset).
+///
+/// If entity doesn't match `Query<EntitySystem::Data, EntitySystem::Filter>` of the entity system,
+/// it's skipped.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_entity_system::prelude::*;
+/// #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// enum Guard {
+///     Patrol,
+///     Chase,
+/// }
+///
+/// #[derive(Component)]
+/// struct Distance(i32);
+///
+/// #[derive(Component)]
+/// struct Speed(i32);
+///
+/// fn patrol(data: Data<&Distance>) -> Option<Guard> {
+///     (data.0 < 10).then_some(Guard::Chase)
+/// }
+///
+/// fn chase(data: Data<&Distance>) -> Option<Guard> {
+///     (data.0 >= 10).then_some(Guard::Patrol)
+/// }
+///
+/// fn run(mut data: Data<&mut Speed>) {
+///     data.0 = 2;
+/// }
+///
+/// fn walk(mut data: Data<&mut Speed>) {
+///     data.0 = 1;
+/// }
+///
+/// let state_machine = StateMachine::new()
+///     .state(Guard::Patrol, patrol)
+///     .state(Guard::Chase, chase)
+///     .on_enter(Guard::Chase, run)
+///     .on_exit(Guard::Chase, walk);
+///
+/// let mut world = World::new();
+/// let entity = world
+///     .spawn((CurrentState(Guard::Patrol), Distance(5), Speed(1)))
+///     .id();
+///
+/// let mut schedule = Schedule::default();
+/// schedule.add_systems(state_machine.into_system());
+///
+/// schedule.run(&mut world);
+/// assert_eq!(world.get::<CurrentState<Guard>>(entity).unwrap().0, Guard::Chase);
+/// assert_eq!(world.get::<Speed>(entity).unwrap().0, 2);
+/// ```
+pub struct StateMachine<S: Clone + Eq + Hash + Send + Sync + 'static> {
+    states: HashMap<S, StateSystems<S>>,
+}
+
+impl<S: Clone + Eq + Hash + Send + Sync + 'static> Default for StateMachine<S> {
+    fn default() -> Self {
+        StateMachine {
+            states: HashMap::default(),
+        }
+    }
+}
+
+impl<S: Clone + Eq + Hash + Send + Sync + 'static> StateMachine<S> {
+    /// Creates state machine without any states
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets entity system that is run for the entities in the `state`.
+    /// Replaces the previous one.
+    pub fn state<Marker>(
+        mut self,
+        state: S,
+        system: impl IntoEntitySystem<(), Option<S>, Marker>,
+    ) -> Self {
+        self.states.entry(state).or_default().update = Some(system.boxed());
+        self
+    }
+
+    /// Adds entity system that is run when entity enters the `state`
+    pub fn on_enter<Marker>(
+        mut self,
+        state: S,
+        system: impl IntoEntitySystem<(), (), Marker>,
+    ) -> Self {
+        self.states
+            .entry(state)
+            .or_default()
+            .on_enter
+            .push(system.boxed());
+        self
+    }
+
+    /// Adds entity system that is run when entity exits the `state`
+    pub fn on_exit<Marker>(
+        mut self,
+        state: S,
+        system: impl IntoEntitySystem<(), (), Marker>,
+    ) -> Self {
+        self.states
+            .entry(state)
+            .or_default()
+            .on_exit
+            .push(system.boxed());
+        self
+    }
+
+    /// Runs state machine once for the `entity`
+    fn run(&mut self, world: &mut World, entity: Entity, state: S, added: bool) {
+        if added {
+            self.run_all(world, entity, &state, |systems| &mut systems.on_enter);
+        }
+
+        let next = match self
+            .states
+            .get_mut(&state)
+            .and_then(|systems| systems.update.as_mut())
+        {
+            Some(system) => system.run((), entity, world).ok().flatten(),
+            None => None,
+        };
+
+        let Some(next) = next.filter(|next| *next != state) else {
+            return;
+        };
+
+        self.run_all(world, entity, &state, |systems| &mut systems.on_exit);
+        self.reset(entity, &state);
+
+        match world.get_mut::<CurrentState<S>>(entity) {
+            Some(mut current) => current.0 = next.clone(),
+            None => return,
+        }
+
+        self.run_all(world, entity, &next, |systems| &mut systems.on_enter);
+    }
+
+    /// Resets the state that entity systems of the `state` store for the `entity`
+    fn reset(&mut self, entity: Entity, state: &S) {
+        let Some(systems) = self.states.get_mut(state) else {
+            return;
+        };
+
+        if let Some(update) = &mut systems.update {
+            update.reset(entity);
+        }
+
+        for system in systems.on_enter.iter_mut().chain(&mut systems.on_exit) {
+            system.reset(entity);
+        }
+    }
+
+    fn run_all(
+        &mut self,
+        world: &mut World,
+        entity: Entity,
+        state: &S,
+        get: impl FnOnce(&mut StateSystems<S>) -> &mut Vec<BoxedEntitySystem>,
+    ) {
+        if let Some(systems) = self.states.get_mut(state) {
+            for system in get(systems) {
+                let _ = system.run((), entity, world);
+            }
+        }
+    }
+
+    /// Turns state machine into exclusive [`System`] that runs it for every entity
+    /// with [`CurrentState<S>`] component.
+    pub fn into_system(mut self) -> impl System<In = (), Out = ()> {
+        IntoSystem::into_system(
+            move |world: &mut World, query: &mut QueryState<(Entity, Ref<CurrentState<S>>)>| {
+                let entities: Vec<(Entity, S, bool)> = query
+                    .iter(world)
+                    .map(|(entity, state)| (entity, state.0.clone(), state.is_added()))
+                    .collect();
+
+                for (entity, state, added) in entities {
+                    self.run(world, entity, state, added);
+                }
+            },
+        )
+    }
+}
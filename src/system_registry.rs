@@ -0,0 +1,369 @@
+//! Running [`EntitySystem`](crate::EntitySystem)s once for a single entity.
+//! Similar to one-shot systems, see [`World::run_system`]
+
+use crate::{
+    dyn_entity_system::{BoxedEntitySystem, CachedEntitySystem, DynEntitySystem},
+    into_entity_system::IntoEntitySystem,
+    EntityMismatch,
+};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    system::{Commands, EntityCommands},
+    world::World,
+};
+use std::{fmt, hash::Hash, marker::PhantomData};
+
+/// Component that stores [`EntitySystem`](crate::EntitySystem) registered with
+/// [`register_entity_system`](WorldEntitySystemExt::register_entity_system)
+#[derive(Component)]
+struct RegisteredEntitySystem<In: 'static, Out: 'static>(Option<BoxedEntitySystem<In, Out>>);
+
+/// Identifier of the registered [`EntitySystem`](crate::EntitySystem). See [`WorldEntitySystemExt::register_entity_system`]
+pub struct EntitySystemId<In = (), Out = ()> {
+    entity: Entity,
+    marker: PhantomData<fn(In) -> Out>,
+}
+
+impl<In, Out> EntitySystemId<In, Out> {
+    /// Entity that stores registered system
+    #[inline]
+    pub fn entity(&self) -> Entity {
+        self.entity
+    }
+}
+
+impl<In, Out> Clone for EntitySystemId<In, Out> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<In, Out> Copy for EntitySystemId<In, Out> {}
+
+impl<In, Out> PartialEq for EntitySystemId<In, Out> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.entity == other.entity
+    }
+}
+
+impl<In, Out> Eq for EntitySystemId<In, Out> {}
+
+impl<In, Out> Hash for EntitySystemId<In, Out> {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.entity.hash(state);
+    }
+}
+
+impl<In, Out> fmt::Debug for EntitySystemId<In, Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EntitySystemId").field(&self.entity).finish()
+    }
+}
+
+/// Error returned by [`run_registered_entity_system`](WorldEntitySystemExt::run_registered_entity_system).
+/// Similar to [`RegisteredSystemError`](bevy_ecs::system::RegisteredSystemError)
+pub enum RegisteredEntitySystemError<In = (), Out = ()> {
+    /// System with such id isn't registered, or was removed
+    NotRegistered(EntitySystemId<In, Out>),
+    /// System tried to run itself recursively
+    Recursive(EntitySystemId<In, Out>),
+    /// Entity doesn't match `Query<EntitySystem::Data, EntitySystem::Filter>` of the system
+    Mismatch(EntityMismatch),
+}
+
+impl<In, Out> From<EntityMismatch> for RegisteredEntitySystemError<In, Out> {
+    #[inline]
+    fn from(mismatch: EntityMismatch) -> Self {
+        RegisteredEntitySystemError::Mismatch(mismatch)
+    }
+}
+
+impl<In, Out> Clone for RegisteredEntitySystemError<In, Out> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<In, Out> Copy for RegisteredEntitySystemError<In, Out> {}
+
+impl<In, Out> PartialEq for RegisteredEntitySystemError<In, Out> {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::NotRegistered(a), Self::NotRegistered(b)) => a == b,
+            (Self::Recursive(a), Self::Recursive(b)) => a == b,
+            (Self::Mismatch(a), Self::Mismatch(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl<In, Out> Eq for RegisteredEntitySystemError<In, Out> {}
+
+impl<In, Out> fmt::Debug for RegisteredEntitySystemError<In, Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotRegistered(id) => f.debug_tuple("NotRegistered").field(id).finish(),
+            Self::Recursive(id) => f.debug_tuple("Recursive").field(id).finish(),
+            Self::Mismatch(mismatch) => f.debug_tuple("Mismatch").field(mismatch).finish(),
+        }
+    }
+}
+
+impl<In, Out> fmt::Display for RegisteredEntitySystemError<In, Out> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotRegistered(id) => write!(f, "{id:?} isn't registered"),
+            Self::Recursive(id) => write!(f, "{id:?} can't be run recursively"),
+            Self::Mismatch(mismatch) => mismatch.fmt(f),
+        }
+    }
+}
+
+impl<In, Out> std::error::Error for RegisteredEntitySystemError<In, Out> {}
+
+/// Extension trait for [`World`] to run [`EntitySystem`](crate::EntitySystem)s for a single entity
+pub trait WorldEntitySystemExt {
+    /// Runs `system` once for the `entity`, passing `input` to it and applying deferred mutations afterwards.
+    /// Returns [`EntityMismatch`] if entity doesn't match
+    /// `Query<EntitySystem::Data, EntitySystem::Filter>`.
+    ///
+    /// State of the system is created for this run and dropped afterwards.
+    /// Use [`register_entity_system`](WorldEntitySystemExt::register_entity_system)
+    /// to run the same system repeatedly.
+    ///
+    /// ```
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_entity_system::prelude::*;
+    /// #[derive(Component)]
+    /// struct Count(i32);
+    ///
+    /// fn increment_count(mut data: Data<&mut Count>) -> i32 {
+    ///     data.0 += 1;
+    ///     data.0
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// let entity = world.spawn(Count(0)).id();
+    /// let empty = world.spawn_empty().id();
+    ///
+    /// assert_eq!(world.run_entity_system(entity, increment_count, ()), Ok(1));
+    /// assert_eq!(
+    ///     world.run_entity_system(empty, increment_count, ()),
+    ///     Err(EntityMismatch(empty))
+    /// );
+    /// ```
+    fn run_entity_system<In, Out, Marker, T: IntoEntitySystem<In, Out, Marker>>(
+        &mut self,
+        entity: Entity,
+        system: T,
+        input: In,
+    ) -> Result<Out, EntityMismatch>;
+
+    /// Registers `system` in the world, so it can be run with
+    /// [`run_registered_entity_system`](WorldEntitySystemExt::run_registered_entity_system).
+    /// State of the query and params of the system is cached between runs.
+    fn register_entity_system<
+        In: 'static,
+        Out: 'static,
+        Marker,
+        T: IntoEntitySystem<In, Out, Marker>,
+    >(
+        &mut self,
+        system: T,
+    ) -> EntitySystemId<In, Out>;
+
+    /// Runs registered system once for the `entity`, passing `input` to it and applying deferred mutations afterwards.
+    /// Returns [`RegisteredEntitySystemError`] if system with such id isn't registered,
+    /// if system runs itself recursively or if entity doesn't match
+    /// `Query<EntitySystem::Data, EntitySystem::Filter>`.
+    fn run_registered_entity_system<In: 'static, Out: 'static>(
+        &mut self,
+        id: EntitySystemId<In, Out>,
+        entity: Entity,
+        input: In,
+    ) -> Result<Out, RegisteredEntitySystemError<In, Out>>;
+
+    /// Resets the state that registered system stores for the `entity`.
+    /// See [`EntitySystem::reset`](crate::EntitySystem::reset).
+    /// Returns [`RegisteredEntitySystemError`] if system with such id isn't registered
+    /// or if it's currently running.
+    fn reset_registered_entity_system<In: 'static, Out: 'static>(
+        &mut self,
+        id: EntitySystemId<In, Out>,
+        entity: Entity,
+    ) -> Result<(), RegisteredEntitySystemError<In, Out>>;
+
+    /// Removes registered system from the world.
+    /// Returns `false` if system with such id isn't registered.
+    fn remove_entity_system<In: 'static, Out: 'static>(
+        &mut self,
+        id: EntitySystemId<In, Out>,
+    ) -> bool;
+}
+
+impl WorldEntitySystemExt for World {
+    fn run_entity_system<In, Out, Marker, T: IntoEntitySystem<In, Out, Marker>>(
+        &mut self,
+        entity: Entity,
+        system: T,
+        input: In,
+    ) -> Result<Out, EntityMismatch> {
+        CachedEntitySystem::new(system.into_entity_system()).run(input, entity, self)
+    }
+
+    fn register_entity_system<
+        In: 'static,
+        Out: 'static,
+        Marker,
+        T: IntoEntitySystem<In, Out, Marker>,
+    >(
+        &mut self,
+        system: T,
+    ) -> EntitySystemId<In, Out> {
+        EntitySystemId {
+            entity: self.spawn(RegisteredEntitySystem(Some(system.boxed()))).id(),
+            marker: PhantomData,
+        }
+    }
+
+    fn run_registered_entity_system<In: 'static, Out: 'static>(
+        &mut self,
+        id: EntitySystemId<In, Out>,
+        entity: Entity,
+        input: In,
+    ) -> Result<Out, RegisteredEntitySystemError<In, Out>> {
+        let mut system = self
+            .get_mut::<RegisteredEntitySystem<In, Out>>(id.entity)
+            .ok_or(RegisteredEntitySystemError::NotRegistered(id))?
+            .0
+            .take()
+            .ok_or(RegisteredEntitySystemError::Recursive(id))?;
+
+        let result = system.run(input, entity, self);
+
+        if let Some(mut registered) = self.get_mut::<RegisteredEntitySystem<In, Out>>(id.entity) {
+            registered.0 = Some(system);
+        }
+
+        Ok(result?)
+    }
+
+    fn reset_registered_entity_system<In: 'static, Out: 'static>(
+        &mut self,
+        id: EntitySystemId<In, Out>,
+        entity: Entity,
+    ) -> Result<(), RegisteredEntitySystemError<In, Out>> {
+        self.get_mut::<RegisteredEntitySystem<In, Out>>(id.entity)
+            .ok_or(RegisteredEntitySystemError::NotRegistered(id))?
+            .0
+            .as_mut()
+            .ok_or(RegisteredEntitySystemError::Recursive(id))?
+            .reset(entity);
+
+        Ok(())
+    }
+
+    fn remove_entity_system<In: 'static, Out: 'static>(
+        &mut self,
+        id: EntitySystemId<In, Out>,
+    ) -> bool {
+        match self.get_entity_mut(id.entity) {
+            Some(entity) if entity.contains::<RegisteredEntitySystem<In, Out>>() => {
+                entity.despawn();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Extension trait for [`Commands`] to run [`EntitySystem`](crate::EntitySystem)s for a single entity.
+/// Entities that system can't be run on and systems that aren't registered are ignored.
+pub trait CommandsEntitySystemExt {
+    /// Runs `system` once for the `entity`. See [`WorldEntitySystemExt::run_entity_system`]
+    fn run_entity_system<In: Send + 'static, Out, Marker, T: IntoEntitySystem<In, Out, Marker>>(
+        &mut self,
+        entity: Entity,
+        system: T,
+        input: In,
+    );
+
+    /// Runs registered system once for the `entity`. See [`WorldEntitySystemExt::run_registered_entity_system`]
+    fn run_registered_entity_system<In: Send + 'static, Out: 'static>(
+        &mut self,
+        id: EntitySystemId<In, Out>,
+        entity: Entity,
+        input: In,
+    );
+}
+
+impl CommandsEntitySystemExt for Commands<'_, '_> {
+    fn run_entity_system<In: Send + 'static, Out, Marker, T: IntoEntitySystem<In, Out, Marker>>(
+        &mut self,
+        entity: Entity,
+        system: T,
+        input: In,
+    ) {
+        let system = system.into_entity_system();
+        self.add(move |world: &mut World| {
+            let _ = world.run_entity_system(entity, system, input);
+        });
+    }
+
+    fn run_registered_entity_system<In: Send + 'static, Out: 'static>(
+        &mut self,
+        id: EntitySystemId<In, Out>,
+        entity: Entity,
+        input: In,
+    ) {
+        self.add(move |world: &mut World| {
+            let _ = world.run_registered_entity_system(id, entity, input);
+        });
+    }
+}
+
+/// Extension trait for [`EntityCommands`] to run [`EntitySystem`](crate::EntitySystem)s for this entity.
+/// If system can't be run on the entity or isn't registered, nothing happens.
+pub trait EntityCommandsEntitySystemExt {
+    /// Runs `system` once for this entity. See [`WorldEntitySystemExt::run_entity_system`]
+    fn run_entity_system<In: Send + 'static, Out, Marker, T: IntoEntitySystem<In, Out, Marker>>(
+        &mut self,
+        system: T,
+        input: In,
+    ) -> &mut Self;
+
+    /// Runs registered system once for this entity. See [`WorldEntitySystemExt::run_registered_entity_system`]
+    fn run_registered_entity_system<In: Send + 'static, Out: 'static>(
+        &mut self,
+        id: EntitySystemId<In, Out>,
+        input: In,
+    ) -> &mut Self;
+}
+
+impl EntityCommandsEntitySystemExt for EntityCommands<'_> {
+    fn run_entity_system<In: Send + 'static, Out, Marker, T: IntoEntitySystem<In, Out, Marker>>(
+        &mut self,
+        system: T,
+        input: In,
+    ) -> &mut Self {
+        let system = system.into_entity_system();
+        self.add(move |entity, world: &mut World| {
+            let _ = world.run_entity_system(entity, system, input);
+        })
+    }
+
+    fn run_registered_entity_system<In: Send + 'static, Out: 'static>(
+        &mut self,
+        id: EntitySystemId<In, Out>,
+        input: In,
+    ) -> &mut Self {
+        self.add(move |entity, world: &mut World| {
+            let _ = world.run_registered_entity_system(id, entity, input);
+        })
+    }
+}
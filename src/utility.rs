@@ -0,0 +1,334 @@
+//! Utility AI built out of [`EntitySystem`]s.
+//! Every action has a scorer, and the action with the highest score is run, see [`ChooseBest`]
+
+use crate::{
+    data_match::DataMatch, into_entity_system::IntoEntitySystem, EntitySystem, ReadOnlyEntitySystem,
+};
+use bevy_ecs::{
+    entity::Entity,
+    query::{Or, QueryFilter, QueryItem},
+    system::{lifetimeless::SQuery, ParamSet, SystemParam, SystemParamItem},
+};
+use bevy_entity_system_macros::IntoSystem;
+
+/// Tuple of pairs of scorer and action [`EntitySystem`]s.
+/// Scorers are [`ReadOnlyEntitySystem`]s that return `f32`.
+/// Implemented for tuples of up to 8 elements
+pub trait UtilityOptions: Send + Sync + 'static {
+    /// Input of every action
+    type In;
+    /// Output of every action
+    type Out;
+    /// [`SystemParam`]'s of all the scorers and actions
+    type Param: SystemParam;
+    /// Filter that matches entities for which at least one option can be run
+    type Filter: QueryFilter;
+
+    /// Number of options
+    const LEN: usize;
+
+    /// Runs scorer of the option with the `index` for the `entity`.
+    /// Returns `None` if either scorer or action of the option can't be run for the entity.
+    fn score(
+        &mut self,
+        index: usize,
+        entity: Entity,
+        param_value: &mut SystemParamItem<Self::Param>,
+    ) -> Option<f32>;
+
+    /// Runs action of the option with the `index` for the `entity`.
+    ///
+    /// # Panics
+    /// If action can't be run for the entity.
+    fn run_action(
+        &mut self,
+        index: usize,
+        input: Self::In,
+        entity: Entity,
+        param_value: &mut SystemParamItem<Self::Param>,
+    ) -> Self::Out;
+
+    /// Resets scorer and action of the option with the `index` for the `entity`.
+    /// See [`EntitySystem::reset`]
+    fn reset_option(&mut self, index: usize, entity: Entity);
+
+    /// Resets scorers and actions of all the options for the `entity`. See [`EntitySystem::reset`]
+    fn reset(&mut self, entity: Entity);
+}
+
+/// Conversion of the tuple into [`UtilityOptions`].
+/// Implemented for tuples of pairs of [`IntoEntitySystem`]s
+pub trait IntoUtilityOptions<In, Out, Marker> {
+    /// Converted tuple
+    type Options: UtilityOptions<In = In, Out = Out>;
+
+    /// Converts tuple into [`UtilityOptions`]
+    fn into_options(self) -> Self::Options;
+}
+
+type OptionParam<S, A> = ParamSet<
+    'static,
+    'static,
+    (
+        (
+            SQuery<<S as EntitySystem>::Data, <S as EntitySystem>::Filter>,
+            <S as EntitySystem>::Param,
+        ),
+        (
+            SQuery<<A as EntitySystem>::Data, <A as EntitySystem>::Filter>,
+            <A as EntitySystem>::Param,
+        ),
+    ),
+>;
+
+macro_rules! impl_utility_options {
+    ($len: expr, $(($index: tt, $p: ident, $scorer: ident, $action: ident, $scorer_marker: ident, $action_marker: ident)),*) => {
+        impl<
+            In,
+            Out,
+            $($scorer: ReadOnlyEntitySystem<In = (), Out = f32>, $action: EntitySystem<In = In, Out = Out>),*
+        > UtilityOptions for ($(($scorer, $action),)*)
+        {
+            type In = In;
+            type Out = Out;
+            type Param = ParamSet<'static, 'static, ($(OptionParam<$scorer, $action>,)*)>;
+            type Filter = Or<($((
+                DataMatch<$scorer::Data>,
+                $scorer::Filter,
+                DataMatch<$action::Data>,
+                $action::Filter,
+            ),)*)>;
+
+            const LEN: usize = $len;
+
+            fn score(
+                &mut self,
+                index: usize,
+                entity: Entity,
+                param_value: &mut SystemParamItem<Self::Param>,
+            ) -> Option<f32> {
+                match index {
+                    $($index => {
+                        let mut option = param_value.$p();
+                        if !option.p1().0.contains(entity) {
+                            return None;
+                        }
+
+                        let (mut query, param) = option.p0();
+                        let score = match query.get_mut(entity) {
+                            Ok(data) => Some(self.$index.0.run((), entity, data, param)),
+                            Err(_) => None,
+                        };
+                        score
+                    })*
+                    _ => panic!("option index {index} is out of bounds"),
+                }
+            }
+
+            fn run_action(
+                &mut self,
+                index: usize,
+                input: Self::In,
+                entity: Entity,
+                param_value: &mut SystemParamItem<Self::Param>,
+            ) -> Self::Out {
+                match index {
+                    $($index => {
+                        let mut option = param_value.$p();
+                        let (mut query, param) = option.p1();
+                        let data = query.get_mut(entity).unwrap();
+                        self.$index.1.run(input, entity, data, param)
+                    })*
+                    _ => panic!("option index {index} is out of bounds"),
+                }
+            }
+
+            fn reset_option(&mut self, index: usize, entity: Entity) {
+                match index {
+                    $($index => {
+                        self.$index.0.reset(entity);
+                        self.$index.1.reset(entity);
+                    })*
+                    _ => panic!("option index {index} is out of bounds"),
+                }
+            }
+
+            #[inline]
+            fn reset(&mut self, entity: Entity) {
+                $(
+                    self.$index.0.reset(entity);
+                    self.$index.1.reset(entity);
+                )*
+            }
+        }
+
+        impl<
+            In,
+            Out,
+            $(
+                $scorer: IntoEntitySystem<(), f32, $scorer_marker, EntitySystem: ReadOnlyEntitySystem>,
+                $action: IntoEntitySystem<In, Out, $action_marker>,
+                $scorer_marker,
+                $action_marker
+            ),*
+        > IntoUtilityOptions<In, Out, ($(($scorer_marker, $action_marker),)*)> for ($(($scorer, $action),)*)
+        {
+            type Options = ($(($scorer::EntitySystem, $action::EntitySystem),)*);
+
+            #[inline]
+            fn into_options(self) -> Self::Options {
+                ($((self.$index.0.into_entity_system(), self.$index.1.into_entity_system()),)*)
+            }
+        }
+    };
+}
+
+impl_utility_options!(1, (0, p0, SA, AA, MSA, MAA));
+impl_utility_options!(2, (0, p0, SA, AA, MSA, MAA), (1, p1, SB, AB, MSB, MAB));
+impl_utility_options!(
+    3,
+    (0, p0, SA, AA, MSA, MAA),
+    (1, p1, SB, AB, MSB, MAB),
+    (2, p2, SC, AC, MSC, MAC)
+);
+impl_utility_options!(
+    4,
+    (0, p0, SA, AA, MSA, MAA),
+    (1, p1, SB, AB, MSB, MAB),
+    (2, p2, SC, AC, MSC, MAC),
+    (3, p3, SD, AD, MSD, MAD)
+);
+impl_utility_options!(
+    5,
+    (0, p0, SA, AA, MSA, MAA),
+    (1, p1, SB, AB, MSB, MAB),
+    (2, p2, SC, AC, MSC, MAC),
+    (3, p3, SD, AD, MSD, MAD),
+    (4, p4, SE, AE, MSE, MAE)
+);
+impl_utility_options!(
+    6,
+    (0, p0, SA, AA, MSA, MAA),
+    (1, p1, SB, AB, MSB, MAB),
+    (2, p2, SC, AC, MSC, MAC),
+    (3, p3, SD, AD, MSD, MAD),
+    (4, p4, SE, AE, MSE, MAE),
+    (5, p5, SF, AF, MSF, MAF)
+);
+impl_utility_options!(
+    7,
+    (0, p0, SA, AA, MSA, MAA),
+    (1, p1, SB, AB, MSB, MAB),
+    (2, p2, SC, AC, MSC, MAC),
+    (3, p3, SD, AD, MSD, MAD),
+    (4, p4, SE, AE, MSE, MAE),
+    (5, p5, SF, AF, MSF, MAF),
+    (6, p6, SG, AG, MSG, MAG)
+);
+impl_utility_options!(
+    8,
+    (0, p0, SA, AA, MSA, MAA),
+    (1, p1, SB, AB, MSB, MAB),
+    (2, p2, SC, AC, MSC, MAC),
+    (3, p3, SD, AD, MSD, MAD),
+    (4, p4, SE, AE, MSE, MAE),
+    (5, p5, SF, AF, MSF, MAF),
+    (6, p6, SG, AG, MSG, MAG),
+    (7, p7, SH, AH, MSH, MAH)
+);
+
+/// [`EntitySystem`] that runs scorers of all the options and then runs the action
+/// of the option with the highest score. If several options have the same score, the first one is chosen.
+///
+/// Options, which scorer or action can't be run for the entity, or which score is `NaN`, are skipped.
+/// Returns output of the action or `None` if all the options were skipped.
+/// Entity has to match at least one of the options.
+///
+/// Options that weren't chosen are reset for the entity, see [`EntitySystem::reset`].
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_entity_system::prelude::*;
+/// # use bevy_entity_system::utility::choose_best;
+/// #[derive(Component)]
+/// struct Hunger(f32);
+///
+/// #[derive(Component)]
+/// struct Fatigue(f32);
+///
+/// fn hunger(data: Data<&Hunger>) -> f32 {
+///     data.0
+/// }
+///
+/// fn fatigue(data: Data<&Fatigue>) -> f32 {
+///     data.0
+/// }
+///
+/// fn eat(mut data: Data<&mut Hunger>) {
+///     data.0 = 0.0;
+/// }
+///
+/// fn sleep(mut data: Data<&mut Fatigue>) {
+///     data.0 = 0.0;
+/// }
+///
+/// let mut world = World::new();
+/// let entity = world.spawn((Hunger(0.7), Fatigue(0.4))).id();
+///
+/// let ai = world.register_entity_system(choose_best(((hunger, eat), (fatigue, sleep))));
+///
+/// assert_eq!(world.run_registered_entity_system(ai, entity, ()), Ok(Some(())));
+/// assert_eq!(world.get::<Hunger>(entity).unwrap().0, 0.0);
+/// assert_eq!(world.get::<Fatigue>(entity).unwrap().0, 0.4);
+/// ```
+#[derive(IntoSystem, Clone)]
+pub struct ChooseBest<T: UtilityOptions>(T);
+
+impl<T: UtilityOptions> EntitySystem for ChooseBest<T> {
+    type Data = Entity;
+    type Filter = T::Filter;
+    type Param = T::Param;
+
+    type In = T::In;
+    type Out = Option<T::Out>;
+
+    fn run(
+        &mut self,
+        input: Self::In,
+        entity: Entity,
+        _data_value: QueryItem<Self::Data>,
+        mut param_value: SystemParamItem<Self::Param>,
+    ) -> Self::Out {
+        let mut best: Option<(usize, f32)> = None;
+
+        for index in 0..T::LEN {
+            let Some(score) = self.0.score(index, entity, &mut param_value) else {
+                continue;
+            };
+
+            if !score.is_nan() && best.map_or(true, |(_, best)| score > best) {
+                best = Some((index, score));
+            }
+        }
+
+        let chosen = best.map(|(index, _)| index);
+        for index in (0..T::LEN).filter(|index| Some(*index) != chosen) {
+            self.0.reset_option(index, entity);
+        }
+
+        chosen.map(|index| self.0.run_action(index, input, entity, &mut param_value))
+    }
+
+    #[inline]
+    fn reset(&mut self, entity: Entity) {
+        self.0.reset(entity);
+    }
+}
+
+/// See [`ChooseBest`]
+#[inline]
+pub fn choose_best<In, Out, Marker, T: IntoUtilityOptions<In, Out, Marker>>(
+    options: T,
+) -> ChooseBest<T::Options> {
+    ChooseBest(options.into_options())
+}
@@ -0,0 +1,49 @@
+//! Runs in it's own process, so it can initialize the global `ComputeTaskPool`
+//! with several threads, regardless of the other tests
+
+use bevy_ecs::prelude::*;
+use bevy_entity_system::prelude::*;
+use bevy_tasks::{ComputeTaskPool, TaskPoolBuilder};
+use std::{
+    collections::HashSet,
+    thread::{self, ThreadId},
+};
+
+#[test]
+fn par_system_multi_threaded_test() {
+    #[derive(Component)]
+    struct Count(u32);
+
+    #[derive(Resource)]
+    struct Step(u32);
+
+    fn increment_count(mut data: Data<&mut Count>, step: Res<Step>) -> (u32, ThreadId) {
+        data.0 += step.0;
+        (data.0, thread::current().id())
+    }
+
+    let pool = ComputeTaskPool::get_or_init(|| TaskPoolBuilder::new().num_threads(4).build());
+    assert_eq!(pool.thread_num(), 4);
+
+    let mut world = World::new();
+    world.insert_resource(Step(2));
+    world.spawn_batch((0..10_000).map(|_| Count(1)));
+
+    let system = world.register_system(increment_count.into_par_system_with_output(
+        |(sum, threads): &mut (u32, HashSet<ThreadId>), (count, thread)| {
+            *sum += count;
+            threads.insert(thread);
+        },
+        |(sum, threads), (other_sum, other_threads)| {
+            *sum += other_sum;
+            threads.extend(other_threads);
+        },
+    ));
+
+    let (sum, threads) = world.run_system(system).unwrap();
+    assert_eq!(sum, 30_000);
+    assert!(threads.len() > 1);
+
+    let (sum, _) = world.run_system(system).unwrap();
+    assert_eq!(sum, 50_000);
+}